@@ -1,15 +1,22 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use codec::Codec;
-pub use pallet_betting::rpc::{RpcError, RpcResult};
-use sp_runtime::traits::MaybeDisplay;
+pub use pallet_betting::{
+    rpc::{RpcError, RpcResult},
+    OutcomeIndex,
+};
+use sp_runtime::{sp_std::vec::Vec, traits::MaybeDisplay};
 
 // Here we declare the runtime API. It is implemented it the `impl` block in
 // runtime file (the `runtime/src/lib.rs`)
 sp_api::decl_runtime_apis! {
-    pub trait BettingApi<AccountId, Match> where
-        AccountId: Codec + MaybeDisplay,
+    pub trait BettingApi<MatchId, Match, AccountId, Balance> where
+        MatchId: Codec + MaybeDisplay,
         Match: Codec,
+        AccountId: Codec,
+        Balance: Codec,
     {
-        fn get_match(match_id: AccountId) -> RpcResult<Match>;
+        fn get_match(match_id: MatchId) -> RpcResult<Match>;
+        fn get_positions(match_id: MatchId, who: AccountId) -> RpcResult<Vec<(OutcomeIndex, Balance)>>;
+        fn quote_shares(match_id: MatchId, outcome: OutcomeIndex, shares: Balance) -> RpcResult<Balance>;
     }
 }
@@ -5,7 +5,7 @@ use jsonrpsee::{
     types::error::{CallError, ErrorObject},
 };
 pub use pallet_betting_rpc_runtime_api::BettingApi as BettingRuntimeApi;
-use pallet_betting_rpc_runtime_api::RpcError as BettingRpcError;
+use pallet_betting_rpc_runtime_api::{OutcomeIndex, RpcError as BettingRpcError};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::MaybeDisplay;
@@ -14,9 +14,26 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 #[rpc(client, server)]
-pub trait BettingApi<BlockHash, AccountId, Match> {
+pub trait BettingApi<BlockHash, MatchId, Match, AccountId, Balance> {
     #[method(name = "betting_getMatch")]
-    fn get_match(&self, match_id: AccountId, at: Option<BlockHash>) -> RpcResult<Match>;
+    fn get_match(&self, match_id: MatchId, at: Option<BlockHash>) -> RpcResult<Match>;
+
+    #[method(name = "betting_getPositions")]
+    fn get_positions(
+        &self,
+        match_id: MatchId,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(OutcomeIndex, Balance)>>;
+
+    #[method(name = "betting_quoteShares")]
+    fn quote_shares(
+        &self,
+        match_id: MatchId,
+        outcome: OutcomeIndex,
+        shares: Balance,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Balance>;
 }
 
 /// A struct that implements the `BettingApi`.
@@ -37,16 +54,19 @@ impl<C, Block> BettingPallet<C, Block> {
     }
 }
 
-impl<C, Block, AccountId, Match> BettingApiServer<<Block as BlockT>::Hash, AccountId, Match>
+impl<C, Block, MatchId, Match, AccountId, Balance>
+    BettingApiServer<<Block as BlockT>::Hash, MatchId, Match, AccountId, Balance>
     for BettingPallet<C, Block>
 where
     Block: sp_runtime::traits::Block,
     C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
-    C::Api: BettingRuntimeApi<Block, AccountId, Match>,
-    AccountId: Codec + MaybeDisplay + Send + Sync + 'static,
+    C::Api: BettingRuntimeApi<Block, MatchId, Match, AccountId, Balance>,
+    MatchId: Codec + MaybeDisplay + Send + Sync + 'static,
     Match: Codec + Send + Sync + 'static,
+    AccountId: Codec + Send + Sync + 'static,
+    Balance: Codec + Send + Sync + 'static,
 {
-    fn get_match(&self, match_id: AccountId, at: Option<Block::Hash>) -> RpcResult<Match> {
+    fn get_match(&self, match_id: MatchId, at: Option<Block::Hash>) -> RpcResult<Match> {
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
         self.client
             .runtime_api()
@@ -54,6 +74,35 @@ where
             .map_err(runtime_error)?
             .map_err(betting_rpc_error)
     }
+
+    fn get_positions(
+        &self,
+        match_id: MatchId,
+        who: AccountId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Vec<(OutcomeIndex, Balance)>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .get_positions(&at, match_id, who)
+            .map_err(runtime_error)?
+            .map_err(betting_rpc_error)
+    }
+
+    fn quote_shares(
+        &self,
+        match_id: MatchId,
+        outcome: OutcomeIndex,
+        shares: Balance,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Balance> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .quote_shares(&at, match_id, outcome, shares)
+            .map_err(runtime_error)?
+            .map_err(betting_rpc_error)
+    }
 }
 
 const RUNTIME_ERROR: i32 = 1;
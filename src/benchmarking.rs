@@ -5,10 +5,17 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Betting;
 use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{traits::Hooks, weights::Weight};
 use frame_system::RawOrigin;
 
 
-fn create_match<T: Config>(result: Option<MatchResult>) -> T::AccountId {
+fn new_team<T: Config>(name: Vec<u8>) -> TeamId {
+    Betting::<T>::register_team(T::TeamOrigin::successful_origin(), name, None)
+        .expect("the team is registered");
+    NextTeamId::<T>::get() - 1
+}
+
+fn create_match<T: Config>(result: Option<OutcomeIndex>) -> (T::AccountId, MatchId) {
     let caller: T::AccountId = account("creator", 0, 0);
     T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 1000u32.into());
 
@@ -17,26 +24,44 @@ fn create_match<T: Config>(result: Option<MatchResult>) -> T::AccountId {
 
 
     let betting_match = Match {
+        creator: caller.clone(),
         start,
         length,
-        team1: <BoundedVec<_, T::MaxTeamNameLength>>::try_from("team1".as_bytes().to_vec())
-            .unwrap(),
-        team2: <BoundedVec<_, T::MaxTeamNameLength>>::try_from("team2".as_bytes().to_vec())
+        teams: None,
+        outcomes: [b"team1".to_vec(), b"team2".to_vec(), DRAW.to_vec()]
+            .into_iter()
+            .map(|name| Outcome { name: name.try_into().unwrap(), total: Zero::zero() })
+            .collect::<Vec<_>>()
+            .try_into()
             .unwrap(),
+        market: Some(ScoreMarket::MatchResult),
+        parent: None,
+        score: None,
+        voided: false,
         result,
-        bets: Default::default(),
+        dispute_end: None,
+        asset: None,
+        bet_count: 0,
+        rollover: Zero::zero(),
+        commission: Permill::zero(),
+        settled_pot: None,
+        claimed_stakes: Zero::zero(),
+        paid_winnings: Zero::zero(),
         deposit: T::MatchDeposit::get()
     };
 
     let match_hash = Betting::<T>::get_match_hash(betting_match.clone());
 
-    <MatchHashes<T>>::insert(&match_hash, caller.clone());
-    <Matches<T>>::insert(&caller, betting_match);
+    let match_id = <NextMatchId<T>>::get();
+    <MatchHashes<T>>::insert(&match_hash, match_id);
+    <Matches<T>>::insert(match_id, betting_match);
+    <MatchesByCreator<T>>::insert(&caller, match_id, ());
+    <NextMatchId<T>>::put(match_id + 1);
 
-    caller
+    (caller, match_id)
 }
 
-fn add_bet<T: Config>(user: &'static str, match_id: AccountIdOf<T>, a: u32, r: MatchResult) {
+fn add_bet<T: Config>(user: &'static str, match_id: MatchId, a: u32, r: OutcomeIndex) {
     let caller = account(user, 0, 0);
     T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 10u32.into());
     let origin = <T::RuntimeOrigin>::from(RawOrigin::Signed(caller));
@@ -48,54 +73,476 @@ fn add_bet<T: Config>(user: &'static str, match_id: AccountIdOf<T>, a: u32, r: M
     );
 }
 
+fn add_bets<T: Config>(match_id: MatchId, b: u32) {
+    for i in 0..b {
+        let caller: T::AccountId = account("bettor", i, 0);
+        T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 10u32.into());
+        Betting::<T>::bet(
+            RawOrigin::Signed(caller).into(),
+            match_id,
+            T::Currency::minimum_balance(),
+            i % 3,
+        )
+        .expect("the bet is placed");
+    }
+}
+
+fn add_losing_bets<T: Config>(match_id: MatchId, b: u32) {
+    for i in 0..b {
+        let caller: T::AccountId = account("bettor", i, 0);
+        T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 10u32.into());
+        Betting::<T>::bet(
+            RawOrigin::Signed(caller).into(),
+            match_id,
+            T::Currency::minimum_balance(),
+            MatchResult::Team2Victory.index(),
+        )
+        .expect("the bet is placed");
+    }
+}
+
+fn new_amm_market<T: Config>() -> MatchId {
+    let caller: T::AccountId = account("creator", 0, 0);
+    T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 1000u32.into());
+    let outcomes: Vec<Vec<u8>> = (0..T::MaxOutcomes::get()).map(|i| vec![b'a', i as u8]).collect();
+    let subsidy = T::Currency::minimum_balance() * 100u32.into();
+    let origin = <T::RuntimeOrigin>::from(RawOrigin::Signed(caller));
+    Betting::<T>::create_amm_market(origin, outcomes, subsidy, 10u32.into(), 10u32.into())
+        .expect("the market is created");
+    NextMatchId::<T>::get() - 1
+}
+
+fn create_fixed_odds_markets<T: Config>(l: u32) -> Vec<MatchId> {
+    let caller: T::AccountId = account("bookmaker", 0, 0);
+    T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 10000u32.into());
+    let bond = T::Currency::minimum_balance() * 1000u32.into();
+    (0..l)
+        .map(|i| {
+            let origin = <T::RuntimeOrigin>::from(RawOrigin::Signed(caller.clone()));
+            let outcomes = vec![vec![b'a', i as u8], vec![b'b', i as u8]];
+            let odds = vec![FixedU128::saturating_from_integer(2u32); 2];
+            Betting::<T>::create_fixed_odds_market(origin, outcomes, odds, bond, 10u32.into(), 10u32.into())
+                .expect("the market is created");
+            NextMatchId::<T>::get() - 1
+        })
+        .collect()
+}
+
+fn new_parlay<T: Config>(l: u32) -> (T::AccountId, Vec<MatchId>, ParlayId) {
+    let match_ids = create_fixed_odds_markets::<T>(l);
+    let caller: T::AccountId = whitelisted_caller();
+    T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 100u32.into());
+    let legs = match_ids.iter().map(|match_id| (*match_id, 0)).collect();
+    let stake = T::Currency::minimum_balance() * 10u32.into();
+    Betting::<T>::place_parlay(RawOrigin::Signed(caller.clone()).into(), legs, stake)
+        .expect("the parlay is placed");
+    (caller, match_ids, NextParlayId::<T>::get() - 1)
+}
+
+// Registers the most teams whose league has at most `m` matches
+fn league_teams<T: Config>(m: u32) -> Vec<TeamId> {
+    let teams = (2..=T::MaxOutcomes::get())
+        .take_while(|t| TournamentFormat::League.scheduled_matches(*t) <= m)
+        .last()
+        .unwrap_or(2);
+    (0..teams).map(|i| new_team::<T>(vec![b't', i as u8])).collect()
+}
+
+fn new_league<T: Config>(m: u32) -> (T::AccountId, TournamentId) {
+    let caller: T::AccountId = whitelisted_caller();
+    T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 10000u32.into());
+    Betting::<T>::create_tournament(
+        RawOrigin::Signed(caller.clone()).into(),
+        TournamentFormat::League,
+        league_teams::<T>(m),
+        10u32.into(),
+        10u32.into(),
+        10u32.into(),
+        Permill::zero(),
+    )
+    .expect("the league is created");
+    (caller, NextTournamentId::<T>::get() - 1)
+}
+
 benchmarks! {
     create_match_to_bet {
         // setup initial state
         let caller: T::AccountId = whitelisted_caller();
         T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 10u32.into());
-        let team1 = "team1".as_bytes().to_vec();
-        let team2 = "team2".as_bytes().to_vec();
+        let team1 = new_team::<T>("team1".as_bytes().to_vec());
+        let team2 = new_team::<T>("team2".as_bytes().to_vec());
+        let start = T::BlockNumber::from(10u32);
+        let length = T::BlockNumber::from(10u32);
+    }: _(RawOrigin::Signed(caller.clone()), team1, team2, start, length, Permill::zero(), None) //execute extrinsic or function
+    verify {
+        assert!(MatchesByCreator::<T>::contains_key(&caller, 0)); //verify final state
+    }
+
+    register_team {
+        let name = vec![b'a'; T::MaxTeamNameLength::get() as usize];
+        let metadata = vec![b'a'; T::MaxTeamMetadataLength::get() as usize];
+        let origin = T::TeamOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, name, Some(metadata))
+    verify {
+        assert!(Teams::<T>::contains_key(0));
+    }
+
+    update_team {
+        let team_id = new_team::<T>(vec![b'a'; T::MaxTeamNameLength::get() as usize]);
+        let name = vec![b'b'; T::MaxTeamNameLength::get() as usize];
+        let metadata = vec![b'b'; T::MaxTeamMetadataLength::get() as usize];
+        let origin = T::TeamOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, team_id, name.clone(), Some(metadata))
+    verify {
+        assert_eq!(Teams::<T>::get(team_id).unwrap().name.to_vec(), name);
+    }
+
+    create_market {
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 10u32.into());
+        let outcomes: Vec<Vec<u8>> = (0..T::MaxOutcomes::get())
+            .map(|i| vec![b'a'; T::MaxTeamNameLength::get() as usize - 4].into_iter().chain(i.to_le_bytes()).collect())
+            .collect();
+        let start = T::BlockNumber::from(10u32);
+        let length = T::BlockNumber::from(10u32);
+    }: _(RawOrigin::Signed(caller.clone()), outcomes, start, length, Permill::zero(), None)
+    verify {
+        assert!(MatchesByCreator::<T>::contains_key(&caller, 0));
+    }
+
+    create_derived_market {
+        let (caller, match_id) = create_match::<T>(None);
+    }: _(RawOrigin::Signed(caller.clone()), match_id, ScoreMarket::Total(10), Permill::zero())
+    verify {
+        assert_eq!(DerivedMarkets::<T>::get(match_id).len(), 1);
+    }
+
+    create_fixed_odds_market {
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 10u32.into());
+        let outcomes: Vec<Vec<u8>> = (0..T::MaxOutcomes::get())
+            .map(|i| vec![b'a'; T::MaxTeamNameLength::get() as usize - 4].into_iter().chain(i.to_le_bytes()).collect())
+            .collect();
+        let odds = vec![FixedU128::saturating_from_integer(2u32); outcomes.len()];
+        let bond = T::Currency::minimum_balance() * 10u32.into();
+        let start = T::BlockNumber::from(10u32);
+        let length = T::BlockNumber::from(10u32);
+    }: _(RawOrigin::Signed(caller.clone()), outcomes, odds, bond, start, length)
+    verify {
+        assert!(Books::<T>::contains_key(0));
+    }
+
+    create_amm_market {
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 1000u32.into());
+        let outcomes: Vec<Vec<u8>> = (0..T::MaxOutcomes::get())
+            .map(|i| vec![b'a'; T::MaxTeamNameLength::get() as usize - 4].into_iter().chain(i.to_le_bytes()).collect())
+            .collect();
+        let subsidy = T::Currency::minimum_balance() * 100u32.into();
         let start = T::BlockNumber::from(10u32);
         let length = T::BlockNumber::from(10u32);
-    }: _(RawOrigin::Signed(caller.clone()), team1, team2, start, length) //execute extrinsic or function
+    }: _(RawOrigin::Signed(caller.clone()), outcomes, subsidy, start, length)
     verify {
-        assert!(Matches::<T>::contains_key(&caller)); //verify final state
+        assert!(MarketMakers::<T>::contains_key(0));
     }
 
     bet {
-        let match_id = create_match::<T>(None);
+        let (creator, match_id) = create_match::<T>(None);
+        Betting::<T>::open_in_play(RawOrigin::Signed(creator).into(), match_id)?;
+        frame_system::Pallet::<T>::set_block_number(7u32.into());
         let caller: T::AccountId = whitelisted_caller();
         T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 10u32.into());
         let amount = BalanceOf::<T>::from(T::Currency::minimum_balance());
-        let result = MatchResult::Draw;
-    }: _(RawOrigin::Signed(caller.clone()), match_id.clone(), amount, result)
+        let result = MatchResult::Draw.index();
+    }: _(RawOrigin::Signed(caller.clone()), match_id, amount, result)
+    verify {
+        assert!(Bets::<T>::contains_key((match_id, caller, result)));
+    }
+
+    buy_shares {
+        let match_id = new_amm_market::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 1000u32.into());
+        let shares = T::Currency::minimum_balance() * 10u32.into();
+        let max_cost = T::Currency::minimum_balance() * 1000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), match_id, 0, shares, max_cost)
+    verify {
+        assert!(Bets::<T>::contains_key((match_id, caller, 0)));
+    }
+
+    sell_shares {
+        let match_id = new_amm_market::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 1000u32.into());
+        let shares = T::Currency::minimum_balance() * 10u32.into();
+        let max_cost = T::Currency::minimum_balance() * 1000u32.into();
+        Betting::<T>::buy_shares(RawOrigin::Signed(caller.clone()).into(), match_id, 0, shares, max_cost)?;
+    }: _(RawOrigin::Signed(caller.clone()), match_id, 0, shares, Zero::zero())
+    verify {
+        assert!(!Bets::<T>::contains_key((match_id, caller, 0)));
+    }
+
+    cancel_bet {
+        let (_, match_id) = create_match::<T>(None);
+        add_bet::<T>("user1", match_id, 2, MatchResult::Draw.index());
+        let caller: T::AccountId = account("user1", 0, 0);
+    }: _(RawOrigin::Signed(caller.clone()), match_id, MatchResult::Draw.index())
+    verify {
+        assert!(!Bets::<T>::contains_key((match_id, caller, MatchResult::Draw.index())));
+    }
+
+    reduce_bet {
+        let (_, match_id) = create_match::<T>(None);
+        add_bet::<T>("user1", match_id, 2, MatchResult::Draw.index());
+        let caller: T::AccountId = account("user1", 0, 0);
+        let amount = T::Currency::minimum_balance();
+    }: _(RawOrigin::Signed(caller.clone()), match_id, MatchResult::Draw.index(), amount)
+    verify {
+        assert!(Bets::<T>::contains_key((match_id, caller, MatchResult::Draw.index())));
+    }
+
+    open_in_play {
+        let (caller, match_id) = create_match::<T>(None);
+    }: _(RawOrigin::Signed(caller), match_id)
+    verify {
+        assert!(InPlayMarkets::<T>::contains_key(match_id));
+    }
+
+    suspend_betting {
+        let (creator, match_id) = create_match::<T>(None);
+        Betting::<T>::open_in_play(RawOrigin::Signed(creator).into(), match_id)?;
+        let blocks = T::MaxSuspension::get();
+        let origin = T::ResultOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, match_id, blocks)
+    verify {
+        assert!(InPlayMarkets::<T>::get(match_id).unwrap().suspended_until.is_some());
+    }
+
+    resume_betting {
+        let (creator, match_id) = create_match::<T>(None);
+        Betting::<T>::open_in_play(RawOrigin::Signed(creator).into(), match_id)?;
+        Betting::<T>::suspend_betting(T::ResultOrigin::successful_origin(), match_id, T::MaxSuspension::get())?;
+        let origin = T::ResultOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, match_id)
+    verify {
+        assert!(InPlayMarkets::<T>::get(match_id).unwrap().suspended_until.is_none());
+    }
+
+    place_parlay {
+        let l in 2 .. T::MaxParlayLegs::get();
+        let match_ids = create_fixed_odds_markets::<T>(l);
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() * 100u32.into());
+        let legs: Vec<_> = match_ids.iter().map(|match_id| (*match_id, 0)).collect();
+        let stake = T::Currency::minimum_balance() * 10u32.into();
+    }: _(RawOrigin::Signed(caller), legs, stake)
+    verify {
+        assert!(Parlays::<T>::contains_key(0));
+    }
+
+    settle_parlay {
+        let l in 2 .. T::MaxParlayLegs::get();
+        let (caller, match_ids, parlay_id) = new_parlay::<T>(l);
+        frame_system::Pallet::<T>::set_block_number(25u32.into());
+        for match_id in match_ids {
+            Betting::<T>::set_result(T::ResultOrigin::successful_origin(), match_id, 0)?;
+        }
+        frame_system::Pallet::<T>::set_block_number(25u32.into() + T::DisputePeriod::get());
+    }: _(RawOrigin::Signed(caller), parlay_id)
+    verify {
+        assert!(!Parlays::<T>::contains_key(parlay_id));
+    }
+
+    create_tournament {
+        let m in 1 .. T::MaxTournamentMatches::get();
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::MatchDeposit::get() * T::Currency::minimum_balance() * 10000u32.into());
+        let teams = league_teams::<T>(m);
+    }: _(RawOrigin::Signed(caller), TournamentFormat::League, teams, 10u32.into(), 10u32.into(), 10u32.into(), Permill::zero())
+    verify {
+        assert!(Tournaments::<T>::contains_key(0));
+    }
+
+    advance_tournament {
+        let m in 1 .. T::MaxTournamentMatches::get();
+        let (caller, tournament_id) = new_league::<T>(m);
+        let tournament = Tournaments::<T>::get(tournament_id).unwrap();
+        let end = 11u32 + 10 * tournament.rounds;
+        frame_system::Pallet::<T>::set_block_number(end.into());
+        for fixture in tournament.fixtures.iter() {
+            Betting::<T>::set_result(T::ResultOrigin::successful_origin(), fixture.match_id, 0)?;
+        }
+        frame_system::Pallet::<T>::set_block_number(T::BlockNumber::from(end) + T::DisputePeriod::get());
+    }: _(RawOrigin::Signed(caller), tournament_id)
     verify {
-        let m = Matches::<T>::get(&match_id).unwrap();
-        assert_eq!(m.bets.len(), 1);
+        assert!(!Tournaments::<T>::contains_key(tournament_id));
     }
 
     set_result {
-        let match_id = create_match::<T>(None);
+        let (_, match_id) = create_match::<T>(None);
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        let result = MatchResult::Team1Victory.index();
+        let origin = T::ResultOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, match_id, result)
+    verify {
+        let m = Matches::<T>::get(match_id).unwrap();
+        assert_eq!(m.result, Some(MatchResult::Team1Victory.index()));
+    }
+
+    set_score {
+        let d in 0 .. T::MaxDerivedMarkets::get();
+        let (caller, match_id) = create_match::<T>(None);
+        for line in 0..d {
+            Betting::<T>::create_derived_market(RawOrigin::Signed(caller.clone()).into(), match_id, ScoreMarket::Total(4 * line + 2), Permill::zero())?;
+        }
         frame_system::Pallet::<T>::set_block_number(15u32.into());
-        let result = MatchResult::Team1Victory;
-    }: _(RawOrigin::Root, match_id.clone(), result)
+        let origin = T::ResultOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, match_id, Score { team1: 2, team2: 1 })
     verify {
-        let m = Matches::<T>::get(&match_id).unwrap();
-        assert_eq!(m.result, Some(MatchResult::Team1Victory));
+        let m = Matches::<T>::get(match_id).unwrap();
+        assert_eq!(m.result, Some(MatchResult::Team1Victory.index()));
+    }
+
+    void_match {
+        let d in 0 .. T::MaxDerivedMarkets::get();
+        let (caller, match_id) = create_match::<T>(None);
+        for line in 0..d {
+            Betting::<T>::create_derived_market(RawOrigin::Signed(caller.clone()).into(), match_id, ScoreMarket::Total(4 * line + 2), Permill::zero())?;
+        }
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        let origin = T::ResultOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, match_id)
+    verify {
+        assert!(Matches::<T>::get(match_id).unwrap().voided);
     }
 
     distribute_winnings {
-        let match_id = create_match::<T>(Some(MatchResult::Team1Victory));
+        let b in 1 .. T::MaxBetsPerMatch::get();
+        let (creator, match_id) = create_match::<T>(None);
+        add_bets::<T>(match_id, b);
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        let origin = T::ResultOrigin::successful_origin();
+        Betting::<T>::set_result(origin, match_id, MatchResult::Team1Victory.index())?;
+        frame_system::Pallet::<T>::set_block_number(15u32.into() + T::DisputePeriod::get());
+    }: _(RawOrigin::Signed(creator), match_id)
+    verify {
+        assert_eq!(Matches::<T>::contains_key(match_id), false);
+    }
+
+    cancel_match {
+        let b in 1 .. T::MaxBetsPerMatch::get();
+        let (creator, match_id) = create_match::<T>(None);
+        add_bets::<T>(match_id, b);
+    }: _(RawOrigin::Signed(creator), match_id)
+    verify {
+        assert_eq!(Matches::<T>::contains_key(match_id), false);
+    }
+
+    refund_match {
+        let b in 1 .. T::MaxBetsPerMatch::get();
+        let (_, match_id) = create_match::<T>(None);
+        add_bets::<T>(match_id, b);
+        frame_system::Pallet::<T>::set_block_number(10u32.into() + T::ResultTimeout::get());
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), match_id)
+    verify {
+        assert_eq!(Matches::<T>::contains_key(match_id), false);
+    }
+
+    schedule_settlement {
+        let (creator, match_id) = create_match::<T>(None);
+    }: _(RawOrigin::Signed(creator), match_id, 10u32.into())
+    verify {
+        assert!(ScheduledSettlements::<T>::contains_key(match_id));
+    }
+
+    settle_scheduled {
+        let b in 1 .. T::MaxBetsPerMatch::get();
+        let (_, match_id) = create_match::<T>(None);
+        add_bets::<T>(match_id, b);
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        let origin = T::ResultOrigin::successful_origin();
+        Betting::<T>::set_result(origin, match_id, MatchResult::Team1Victory.index())?;
+        frame_system::Pallet::<T>::set_block_number(15u32.into() + T::DisputePeriod::get());
+    }: _(RawOrigin::Root, match_id)
+    verify {
+        assert_eq!(Matches::<T>::contains_key(match_id), false);
+    }
+
+    dispute_result {
+        let (_, match_id) = create_match::<T>(None);
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        let origin = T::ResultOrigin::successful_origin();
+        Betting::<T>::set_result(origin, match_id, MatchResult::Team1Victory.index())?;
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, T::DisputeBond::get() * 10u32.into());
+    }: _(RawOrigin::Signed(caller.clone()), match_id)
+    verify {
+        assert_eq!(Disputes::<T>::get(match_id).len(), 1);
+    }
+
+    resolve_dispute {
+        let (_, match_id) = create_match::<T>(None);
         frame_system::Pallet::<T>::set_block_number(15u32.into());
-        let result = MatchResult::Team1Victory;
-        add_bet::<T>("user1", match_id.clone(), 1, MatchResult::Team1Victory);
-        add_bet::<T>("user2", match_id.clone(), 2, MatchResult::Team2Victory);
-        add_bet::<T>("user3", match_id.clone(), 3, MatchResult::Draw);
-        add_bet::<T>("user4", match_id.clone(), 4, MatchResult::Draw);
-        add_bet::<T>("user5", match_id.clone(), 5, MatchResult::Team1Victory);
-    }: _(RawOrigin::Signed(match_id.clone()))
-    verify {
-        assert_eq!(Matches::<T>::contains_key(&match_id), false);
+        let origin = T::ResultOrigin::successful_origin();
+        Betting::<T>::set_result(origin, match_id, MatchResult::Team1Victory.index())?;
+        for i in 0 .. T::MaxDisputes::get() {
+            let disputer: T::AccountId = account("disputer", i, 0);
+            T::Currency::make_free_balance_be(&disputer, T::DisputeBond::get() * 10u32.into());
+            Betting::<T>::dispute_result(RawOrigin::Signed(disputer).into(), match_id)?;
+        }
+        let origin = T::DisputeResolutionOrigin::successful_origin();
+    }: _<T::RuntimeOrigin>(origin, match_id, MatchResult::Team1Victory.index())
+    verify {
+        assert_eq!(Disputes::<T>::contains_key(match_id), false);
+    }
+
+    claim_winnings {
+        let b in 1 .. T::MaxBetsPerMatch::get();
+        let (_, match_id) = create_match::<T>(None);
+        add_losing_bets::<T>(match_id, b);
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        // Nobody bet on the result, so the first claim settles every bet
+        let origin = T::ResultOrigin::successful_origin();
+        Betting::<T>::set_result(origin, match_id, MatchResult::Team1Victory.index())?;
+        frame_system::Pallet::<T>::set_block_number(15u32.into() + T::DisputePeriod::get());
+        let caller: T::AccountId = account("bettor", 0, 0);
+    }: _(RawOrigin::Signed(caller), match_id)
+    verify {
+        assert_eq!(Matches::<T>::contains_key(match_id), false);
+    }
+
+    reap_match {
+        let b in 1 .. T::MaxBetsPerMatch::get();
+        let (_, match_id) = create_match::<T>(None);
+        add_losing_bets::<T>(match_id, b);
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        let origin = T::ResultOrigin::successful_origin();
+        Betting::<T>::set_result(origin, match_id, MatchResult::Team1Victory.index())?;
+        frame_system::Pallet::<T>::set_block_number(
+            15u32.into() + T::DisputePeriod::get() + T::ClaimPeriod::get()
+        );
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), match_id)
+    verify {
+        assert_eq!(Matches::<T>::contains_key(match_id), false);
+    }
+
+    finalise_queued_match {
+        let b in 1 .. T::MaxBetsPerMatch::get();
+        let (_, match_id) = create_match::<T>(None);
+        add_bets::<T>(match_id, b);
+        frame_system::Pallet::<T>::set_block_number(15u32.into());
+        let origin = T::ResultOrigin::successful_origin();
+        Betting::<T>::set_result(origin, match_id, MatchResult::Team1Victory.index())?;
+        let now = 15u32.into() + T::DisputePeriod::get();
+        frame_system::Pallet::<T>::set_block_number(now);
+        SettlementCursor::<T>::put(now);
+    }: {
+        Betting::<T>::on_idle(now, Weight::MAX);
+    }
+    verify {
+        assert_eq!(Matches::<T>::contains_key(match_id), false);
     }
 
     impl_benchmark_test_suite!(Betting, crate::mock::new_test_ext(), crate::mock::Test);
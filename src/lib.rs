@@ -19,26 +19,101 @@ pub use weights::WeightInfo;
 
 pub mod rpc;
 
-use codec::{Decode, Encode, HasCompact, MaxEncodedLen};
+pub mod migrations;
+
+mod lmsr;
+
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-    traits::{Currency, ExistenceRequirement::AllowDeath, Get},
+    traits::{
+        fungibles,
+        schedule::{v3::Anon as ScheduleAnon, DispatchTime, LOWEST_PRIORITY},
+        Currency,
+        ExistenceRequirement::AllowDeath,
+        Get, OnUnbalanced, OriginTrait, QueryPreimage, StorePreimage, WithdrawReasons,
+    },
     BoundedVec, RuntimeDebug,
 };
 pub use pallet::*;
 use scale_info::TypeInfo;
+use sp_arithmetic::{
+    helpers_128bit::multiply_by_rational_with_rounding, per_things::Rounding, FixedPointNumber,
+    FixedU128, Permill,
+};
 use sp_io::hashing::blake2_256;
-use sp_runtime::traits::TrailingZeroInput;
-use sp_std::{cmp::Ordering, prelude::*};
+use sp_runtime::traits::{
+    AtLeast32BitUnsigned, One, SaturatedConversion, Saturating, TrailingZeroInput, Zero,
+};
+use sp_std::prelude::*;
 
 type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+pub type AssetIdOf<T> =
+    <<T as Config>::Assets as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
+type CallOf<T> = <T as Config>::RuntimeCall;
+type PalletsOriginOf<T> =
+    <<T as frame_system::Config>::RuntimeOrigin as OriginTrait>::PalletsOrigin;
 
 pub type TeamName<T> = BoundedVec<u8, <T as Config>::MaxTeamNameLength>;
+pub type TeamOf<T> = Team<TeamName<T>, BoundedVec<u8, <T as Config>::MaxTeamMetadataLength>>;
+pub type OutcomeNames<T> = BoundedVec<TeamName<T>, <T as Config>::MaxOutcomes>;
+pub type OutcomesOf<T> = BoundedVec<Outcome<TeamName<T>, BalanceOf<T>>, <T as Config>::MaxOutcomes>;
+pub type BookOf<T> = Book<BoundedVec<FixedU128, <T as Config>::MaxOutcomes>, BalanceOf<T>>;
+pub type ParlayOf<T> =
+    Parlay<AccountIdOf<T>, BoundedVec<Leg, <T as Config>::MaxParlayLegs>, BalanceOf<T>>;
+pub type TournamentOf<T> = Tournament<
+    AccountIdOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+    BoundedVec<TeamId, <T as Config>::MaxOutcomes>,
+    BoundedVec<Fixture, <T as Config>::MaxTournamentMatches>,
+>;
+pub type InPlayOf<T> = InPlay<
+    <T as frame_system::Config>::BlockNumber,
+    BoundedVec<BalanceOf<T>, <T as Config>::MaxOutcomes>,
+>;
+
+/// Identifier of a match, assigned sequentially on creation.
+pub type MatchId = u32;
+
+/// Identifier of a team of the registry, assigned sequentially when it is registered.
+pub type TeamId = u32;
+
+/// Position of an outcome in the list of outcomes of a match.
+pub type OutcomeIndex = u32;
+
+/// Identifier of a parlay, assigned sequentially when it is placed.
+pub type ParlayId = u32;
 
-pub type Bets<T> =
-    BoundedVec<Bet<AccountIdOf<T>, MatchResult, BalanceOf<T>>, <T as Config>::MaxBetsPerMatch>;
+/// Identifier of a tournament, assigned sequentially on creation.
+pub type TournamentId = u32;
 
+pub type MatchOf<T> = Match<
+    AccountIdOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+    OutcomesOf<T>,
+    BalanceOf<T>,
+    AssetIdOf<T>,
+>;
+
+/// Name of the last outcome of the two-team matches.
+pub const DRAW: &[u8] = b"Draw";
+
+/// A team of the registry, referenced by its id in the matches it plays.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Team<Name, Metadata> {
+    /// Name displayed as the outcome of the matches of the team.
+    name: Name,
+    /// Free-form details about the team, such as its country or a link to its crest.
+    metadata: Option<Metadata>,
+}
+
+/// Outcomes of the two-team matches created by `create_match_to_bet`, in the order they are
+/// listed.
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 pub enum MatchResult {
@@ -47,72 +122,485 @@ pub enum MatchResult {
     Draw,
 }
 
+impl MatchResult {
+    /// Returns the index of the outcome in a two-team match.
+    pub const fn index(self) -> OutcomeIndex {
+        self as OutcomeIndex
+    }
+}
+
+impl From<MatchResult> for OutcomeIndex {
+    fn from(result: MatchResult) -> Self {
+        result.index()
+    }
+}
+
+/// Names of the outcomes of the over/under markets.
+pub const OVER: &[u8] = b"Over";
+pub const UNDER: &[u8] = b"Under";
+/// Name of the last outcome of the exact score markets.
+pub const OTHER_SCORE: &[u8] = b"Other";
+
+/// Goals scored by every team of a two-team match.
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
-/// A bet.
-pub struct Bet<AccountId, MatchResult, Balance> {
-    /// Account of the better.
-    bettor: AccountId,
-    /// Bet amount.
-    amount: Balance,
-    /// Result predicted.
-    result: MatchResult,
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Score {
+    pub team1: u32,
+    pub team2: u32,
 }
 
-impl<AccountId, Balance> Ord for Bet<AccountId, MatchResult, Balance>
-where
-    AccountId: Ord,
-    Balance: Ord + HasCompact,
-{
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.bettor
-            .cmp(&other.bettor)
-            .then_with(|| self.amount.cmp(&other.amount))
+/// A market of a two-team match settled from its score.
+/// Lines are in quarters of a goal, so that `10` is a line of 2.5 goals and `-1` a handicap of
+/// -0.25 goals. Quarter lines split every bet in two halves, on the lines a quarter above and
+/// below.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum ScoreMarket {
+    /// Team 1 victory, team 2 victory or draw, in the order of `MatchResult`.
+    MatchResult,
+    /// Total goals `Over` or `Under` the line.
+    Total(u32),
+    /// Victory of team 1 or team 2 once the handicap is added to the goals of team 1.
+    Handicap(i32),
+    /// Every score up to a number of goals per team, then any `Other` score.
+    ExactScore(u32),
+}
+
+impl ScoreMarket {
+    /// Returns the number of outcomes of the market, `None` if it overflows.
+    pub fn outcome_count(self) -> Option<u32> {
+        match self {
+            ScoreMarket::MatchResult => Some(3),
+            ScoreMarket::Total(_) | ScoreMarket::Handicap(_) => Some(2),
+            ScoreMarket::ExactScore(max_goals) => {
+                let scores = max_goals.checked_add(1)?;
+                scores.checked_mul(scores)?.checked_add(1)
+            }
+        }
+    }
+
+    /// Returns the names of the outcomes of the market for a match between two teams.
+    pub fn outcome_names(self, team1: &[u8], team2: &[u8]) -> Vec<Vec<u8>> {
+        match self {
+            ScoreMarket::MatchResult => vec![team1.to_vec(), team2.to_vec(), DRAW.to_vec()],
+            ScoreMarket::Total(_) => vec![OVER.to_vec(), UNDER.to_vec()],
+            ScoreMarket::Handicap(_) => vec![team1.to_vec(), team2.to_vec()],
+            ScoreMarket::ExactScore(max_goals) => (0..=max_goals)
+                .flat_map(|team1| (0..=max_goals).map(move |team2| score_name(team1, team2)))
+                .chain(sp_std::iter::once(OTHER_SCORE.to_vec()))
+                .collect(),
+        }
+    }
+
+    /// Returns how the market is settled with a score.
+    pub fn settle(self, score: Score) -> Settlement {
+        let (team1, team2) = (i64::from(score.team1), i64::from(score.team2));
+        match self {
+            ScoreMarket::MatchResult => Settlement::Win(
+                match team1.cmp(&team2) {
+                    sp_std::cmp::Ordering::Greater => MatchResult::Team1Victory,
+                    sp_std::cmp::Ordering::Less => MatchResult::Team2Victory,
+                    sp_std::cmp::Ordering::Equal => MatchResult::Draw,
+                }
+                .index(),
+            ),
+            ScoreMarket::Total(line) => Settlement::on_line(4 * (team1 + team2) - i64::from(line)),
+            ScoreMarket::Handicap(line) => {
+                Settlement::on_line(4 * (team1 - team2) + i64::from(line))
+            }
+            ScoreMarket::ExactScore(max_goals) => {
+                let scores = max_goals.saturating_add(1);
+                Settlement::Win(if score.team1 <= max_goals && score.team2 <= max_goals {
+                    score.team1 * scores + score.team2
+                } else {
+                    scores.saturating_mul(scores)
+                })
+            }
+        }
     }
 }
 
-impl<AccountId, Balance> PartialOrd for Bet<AccountId, MatchResult, Balance>
-where
-    AccountId: Ord,
-    Balance: Ord + HasCompact,
-{
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// Returns the decimal digits of a number.
+fn digits(mut number: u32) -> Vec<u8> {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'0' + (number % 10) as u8);
+        number /= 10;
+        if number == 0 {
+            break;
+        }
+    }
+    name.reverse();
+    name
+}
+
+/// Returns the name of a score, such as `2-1`.
+fn score_name(team1: u32, team2: u32) -> Vec<u8> {
+    let mut name = digits(team1);
+    name.push(b'-');
+    name.extend(digits(team2));
+    name
+}
+
+/// How the pot of a match is shared once its result is final.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum Settlement {
+    /// The bets on the outcome share the pot.
+    Win(OutcomeIndex),
+    /// Half of every bet is refunded, the bets on the outcome share the other half of the pot.
+    HalfWin(OutcomeIndex),
+    /// Every bet is refunded.
+    Push,
+}
+
+impl Settlement {
+    /// Settles a two-outcome market from the margin of the first outcome over the line, in
+    /// quarters of a goal. Scores are whole goals, so a margin of a quarter is only reached on
+    /// quarter lines, where one half of the bet wins and the other is refunded.
+    fn on_line(margin: i64) -> Self {
+        match margin {
+            0 => Settlement::Push,
+            1 => Settlement::HalfWin(0),
+            -1 => Settlement::HalfWin(1),
+            margin if margin > 0 => Settlement::Win(0),
+            _ => Settlement::Win(1),
+        }
+    }
+
+    /// Returns the outcome whose bets share the pot, if any.
+    pub fn winner(self) -> Option<OutcomeIndex> {
+        match self {
+            Settlement::Win(outcome) | Settlement::HalfWin(outcome) => Some(outcome),
+            Settlement::Push => None,
+        }
+    }
+
+    /// Whether the bets on an outcome get anything back.
+    pub fn pays(self, outcome: OutcomeIndex) -> bool {
+        match self {
+            Settlement::Win(winner) => outcome == winner,
+            Settlement::HalfWin(_) | Settlement::Push => true,
+        }
     }
 }
 
+/// What to do with the pot of a match when nobody bet on its result.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum NoWinnerPolicy {
+    /// Return every bet to its bettor.
+    Refund,
+    /// Add the pot to the next match created by the same creator.
+    Rollover,
+    /// Send the pot to the `Treasury` handler.
+    Treasury,
+}
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+/// The position of a bettor on a result of a match, adding up every bet placed on it.
+pub struct Bet<Balance> {
+    /// Bet amount.
+    amount: Balance,
+    /// Whether the winnings of the bet have been claimed.
+    claimed: bool,
+}
+
+/// A possible outcome of a match and the amount bet on it.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Outcome<Name, Balance> {
+    /// Name of the outcome, e.g. a team or a horse.
+    name: Name,
+    /// Amount bet on the outcome.
+    total: Balance,
+}
+
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo)]
-pub struct Match<BlockNumber, TeamName, Bets, BalanceOf> {
+pub struct Match<AccountId, BlockNumber, Outcomes, BalanceOf, AssetId> {
+    /// Account that created the match and holds the deposit.
+    creator: AccountId,
     /// Starting block of the match.
     start: BlockNumber,
     /// Length of the match (start + length = end).
     length: BlockNumber,
-    /// Team1 name.
-    team1: TeamName,
-    /// Team2 name.
-    team2: TeamName,
+    /// Registered teams of a match between two teams.
+    teams: Option<(TeamId, TeamId)>,
+    /// Possible outcomes, a result is the index of one of them.
+    outcomes: Outcomes,
+    /// Market settled from the score of the match, `None` if the result is set directly.
+    market: Option<ScoreMarket>,
+    /// Match the market is derived from.
+    parent: Option<MatchId>,
+    /// Score reported for the match.
+    score: Option<Score>,
+    /// Whether the match has been abandoned and every bet is refunded.
+    voided: bool,
     /// Result.
-    result: Option<MatchResult>,
-    /// List of bets.
-    bets: Bets,
+    result: Option<OutcomeIndex>,
+    /// Block until which the result can be disputed.
+    dispute_end: Option<BlockNumber>,
+    /// Asset the match is bet in, the native currency if `None`.
+    asset: Option<AssetId>,
+    /// Number of bets placed.
+    bet_count: u32,
+    /// Pot rolled over from a previous match of the creator without winners.
+    rollover: BalanceOf,
+    /// Part of the pot the creator takes as commission.
+    commission: Permill,
+    /// Pot left for the winners once the fees have been taken, set when the match is settled.
+    /// For a match with a market maker, the subsidy moved to the pot. Zero for any other match
+    /// once its bets start being paid.
+    settled_pot: Option<BalanceOf>,
+    /// Amount bet by the winners that have been paid.
+    claimed_stakes: BalanceOf,
+    /// Winnings paid so far, or the stakes refunded if nobody bet on the result.
+    paid_winnings: BalanceOf,
     /// The amount held in reserve of the `depositor`,
     /// To be returned once this recovery process is closed.
     deposit: BalanceOf,
 }
 
+impl<AccountId, BlockNumber, Name, Balance, AssetId, S>
+    Match<AccountId, BlockNumber, BoundedVec<Outcome<Name, Balance>, S>, Balance, AssetId>
+where
+    Balance: Copy + Zero,
+{
+    /// Returns the amount bet on an outcome.
+    pub fn total_on(&self, outcome: OutcomeIndex) -> Balance {
+        self.outcomes
+            .get(outcome as usize)
+            .map_or_else(Zero::zero, |outcome| outcome.total)
+    }
+
+    /// Returns the amount bet on all the outcomes.
+    pub fn total(&self) -> Balance {
+        self.outcomes
+            .iter()
+            .fold(Zero::zero(), |total, outcome| total + outcome.total)
+    }
+
+    /// Whether the match has an outcome with that index.
+    fn has_outcome(&self, outcome: OutcomeIndex) -> bool {
+        (outcome as usize) < self.outcomes.len()
+    }
+
+    fn total_on_mut(&mut self, outcome: OutcomeIndex) -> Option<&mut Balance> {
+        self.outcomes
+            .get_mut(outcome as usize)
+            .map(|outcome| &mut outcome.total)
+    }
+
+    /// Whether the result or the score of the match has been set, or the match voided.
+    pub fn has_result(&self) -> bool {
+        self.result.is_some() || self.score.is_some() || self.voided
+    }
+
+    /// Returns how the pot is shared, from the score if the match has a score market.
+    pub fn settlement(&self) -> Option<Settlement> {
+        match (self.market, self.score) {
+            _ if self.voided => Some(Settlement::Push),
+            (Some(market), Some(score)) => Some(market.settle(score)),
+            _ => self.result.map(Settlement::Win),
+        }
+    }
+}
+
+/// The market maker of a match, which prices the shares of its outcomes with the logarithmic
+/// market scoring rule, and the subsidy reserved from the creator to fund its loss.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct MarketMaker<Balance> {
+    /// Liquidity parameter `b`, the higher the less a trade moves the prices.
+    liquidity: Balance,
+    /// Amount reserved from the creator, at least the loss of the market maker.
+    subsidy: Balance,
+}
+
+/// The decimal odds a bookmaker posts on every outcome of a fixed-odds match, and the bond
+/// reserved from the creator to pay the winnings.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Book<Odds, Balance> {
+    /// Odds of every outcome, in the order of the outcomes.
+    odds: Odds,
+    /// Amount reserved from the creator to pay the winnings.
+    bond: Balance,
+}
+
+impl<S, Balance> Book<BoundedVec<FixedU128, S>, Balance>
+where
+    Balance: AtLeast32BitUnsigned,
+{
+    /// Returns the winnings of a stake on an outcome, on top of the stake itself.
+    pub fn winnings(&self, outcome: OutcomeIndex, stake: Balance) -> Balance {
+        self.odds
+            .get(outcome as usize)
+            .map_or_else(Zero::zero, |odds| {
+                odds.saturating_sub(FixedU128::one())
+                    .saturating_mul_int(stake.saturated_into::<u128>())
+                    .saturated_into()
+            })
+    }
+}
+
+/// The state of a match that takes bets while it is played. A bet placed in-play only shares
+/// the winnings for the part of the match left when it is placed, the rest of its amount is
+/// discounted.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct InPlay<BlockNumber, Discounts> {
+    /// Block until which betting is suspended by the oracle.
+    suspended_until: Option<BlockNumber>,
+    /// Amount discounted from the bets placed in-play on every outcome, in the order of the
+    /// outcomes.
+    discounts: Discounts,
+}
+
+/// How a leg of a parlay has been settled so far.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum LegState {
+    /// The match has not been settled yet.
+    Open,
+    /// The outcome of the leg won.
+    Won,
+    /// The match has been voided or refunded, and the leg is dropped from the parlay.
+    Void,
+}
+
+/// A leg of a parlay: an outcome of a fixed-odds match, at the odds posted when the parlay was
+/// placed.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Leg {
+    /// Id of the match.
+    match_id: MatchId,
+    /// Index of the outcome bet on.
+    outcome: OutcomeIndex,
+    /// Odds of the outcome when the parlay was placed.
+    odds: FixedU128,
+    /// How the leg has been settled so far.
+    state: LegState,
+}
+
+/// A bet on an outcome of several fixed-odds matches of the same bookmaker, which only wins if
+/// every leg wins. The stake is reserved from the bettor, and the winnings at the combined odds
+/// from the bookmaker.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Parlay<AccountId, Legs, Balance> {
+    /// Account that placed the parlay.
+    bettor: AccountId,
+    /// Creator of the matches of the legs, who pays the winnings.
+    bookmaker: AccountId,
+    /// Amount reserved from the bettor.
+    stake: Balance,
+    /// Winnings if every leg wins, reserved from the bookmaker.
+    liability: Balance,
+    /// Legs of the parlay.
+    legs: Legs,
+}
+
+impl<AccountId, S, Balance> Parlay<AccountId, BoundedVec<Leg, S>, Balance>
+where
+    Balance: AtLeast32BitUnsigned + Copy,
+{
+    /// Returns the winnings of the parlay if every leg left wins, on top of the stake, at the
+    /// product of their odds. The void legs are dropped.
+    pub fn winnings(&self) -> Balance {
+        self.legs
+            .iter()
+            .filter(|leg| leg.state != LegState::Void)
+            .fold(FixedU128::one(), |odds, leg| odds.saturating_mul(leg.odds))
+            .saturating_sub(FixedU128::one())
+            .saturating_mul_int(self.stake.saturated_into::<u128>())
+            .saturated_into()
+    }
+}
+
+/// How the matches of a tournament are played.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum TournamentFormat {
+    /// Every team plays every other team once, three points for a win and one for a draw.
+    League,
+    /// The winners of every round play the next one, until the final.
+    Knockout,
+}
+
+impl TournamentFormat {
+    /// Returns the number of matches scheduled when a tournament between a number of teams is
+    /// created, every match of a league or the first round of a knockout.
+    pub fn scheduled_matches(self, teams: u32) -> u32 {
+        match self {
+            TournamentFormat::League => teams.saturating_mul(teams.saturating_sub(1)) / 2,
+            TournamentFormat::Knockout => teams / 2,
+        }
+    }
+}
+
+/// A match of a tournament between two of its teams, by their index in the list of teams.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Fixture {
+    /// Id of the match.
+    match_id: MatchId,
+    /// Index of the first team.
+    home: u32,
+    /// Index of the second team.
+    away: u32,
+    /// How the match settled, recorded when its result is final.
+    result: Option<Settlement>,
+}
+
+/// A league or a knockout cup, which schedules its matches and settles the outright market on
+/// its winner.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Tournament<AccountId, BlockNumber, Teams, Fixtures> {
+    /// Creator of the tournament and of its matches.
+    organizer: AccountId,
+    /// How the matches are played.
+    format: TournamentFormat,
+    /// Registered teams, in the order of the outcomes of the outright market.
+    teams: Teams,
+    /// Time when the first round starts (in blocks).
+    start: BlockNumber,
+    /// Time between the start of two rounds (in blocks).
+    round_length: BlockNumber,
+    /// Duration of every match (in blocks).
+    match_length: BlockNumber,
+    /// Part of the pot of every match taken by the organizer.
+    commission: Permill,
+    /// Number of rounds scheduled so far.
+    rounds: u32,
+    /// Every match of a league, or the matches of the current round of a knockout.
+    fixtures: Fixtures,
+    /// Id of the outright market on the winner.
+    outright: MatchId,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::{
-        pallet_prelude::*, sp_runtime::traits::AccountIdConversion, traits::ReservableCurrency,
+        pallet_prelude::*,
+        sp_runtime::traits::{AccountIdConversion, Dispatchable},
+        storage::with_storage_layer,
+        traits::{BalanceStatus, ReservableCurrency, StorageVersion},
         PalletId,
     };
     use frame_system::pallet_prelude::*;
-    use sp_arithmetic::Perbill;
+
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -125,94 +613,424 @@ pub mod pallet {
         /// The currency trait.
         type Currency: ReservableCurrency<Self::AccountId>;
 
+        /// The fungible assets a match can be bet in instead of the native currency.
+        type Assets: fungibles::Inspect<Self::AccountId, Balance = BalanceOf<Self>>
+            + fungibles::Transfer<Self::AccountId>;
+
         /// The overarching event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
+        /// The overarching call type, used to schedule the settlement of matches.
+        type RuntimeCall: Parameter
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin>
+            + From<Call<Self>>
+            + IsType<<Self as frame_system::Config>::RuntimeCall>;
+
+        /// The scheduler settling the matches whose creator opted in.
+        type Scheduler: ScheduleAnon<Self::BlockNumber, CallOf<Self>, PalletsOriginOf<Self>>;
+
+        /// The preimage provider bounding the scheduled calls.
+        type Preimages: QueryPreimage + StorePreimage;
+
+        /// Number of blocks to wait before trying again to settle a match that could not be
+        /// settled when scheduled.
+        #[pallet::constant]
+        type SettlementRetryPeriod: Get<Self::BlockNumber>;
+
         /// Max length allowed for team names.
         #[pallet::constant]
         type MaxTeamNameLength: Get<u32>;
 
+        /// Max length allowed for the metadata of a team.
+        #[pallet::constant]
+        type MaxTeamMetadataLength: Get<u32>;
+
+        /// Max number of outcomes a match can have, at least 3 to create two-team matches.
+        #[pallet::constant]
+        type MaxOutcomes: Get<u32>;
+
         /// Max number of bets a match can have.
         #[pallet::constant]
         type MaxBetsPerMatch: Get<u32>;
 
+        /// Max number of markets that can be derived from a match.
+        #[pallet::constant]
+        type MaxDerivedMarkets: Get<u32>;
+
         /// The base amount of currency needed to reserve for creating a match.
         #[pallet::constant]
         type MatchDeposit: Get<BalanceOf<Self>>;
 
+        /// The origin which may cancel any match at any time.
+        type CancelOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// The origin which may register and update the teams.
+        type TeamOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// The origin which may set the result of a match, and suspend the betting on a match
+        /// played in-play.
+        type ResultOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Max number of blocks the betting on a match played in-play can be suspended for.
+        #[pallet::constant]
+        type MaxSuspension: Get<Self::BlockNumber>;
+
+        /// Max number of legs of a parlay.
+        #[pallet::constant]
+        type MaxParlayLegs: Get<u32>;
+
+        /// Max number of parlays with a leg on a match.
+        #[pallet::constant]
+        type MaxParlaysPerMatch: Get<u32>;
+
+        /// Max number of matches of a league, or of a round of a knockout.
+        #[pallet::constant]
+        type MaxTournamentMatches: Get<u32>;
+
+        /// Number of blocks after the result is set during which it can be disputed.
+        #[pallet::constant]
+        type DisputePeriod: Get<Self::BlockNumber>;
+
+        /// The amount of currency needed to reserve for disputing a result.
+        #[pallet::constant]
+        type DisputeBond: Get<BalanceOf<Self>>;
+
+        /// Max number of disputes a match result can have.
+        #[pallet::constant]
+        type MaxDisputes: Get<u32>;
+
+        /// The origin which may resolve a disputed result.
+        type DisputeResolutionOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Handler for the bonds slashed when a disputed result is upheld.
+        type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+        /// Number of blocks after the dispute period during which the winners can claim.
+        #[pallet::constant]
+        type ClaimPeriod: Get<Self::BlockNumber>;
+
+        /// Number of blocks after the end of a match after which, if it still has no result,
+        /// anyone can refund it.
+        #[pallet::constant]
+        type ResultTimeout: Get<Self::BlockNumber>;
+
+        /// Part of the deposit of the creator paid to whoever refunds a match without result.
+        #[pallet::constant]
+        type TimeoutIncentive: Get<BalanceOf<Self>>;
+
+        /// What to do with the pot of a match when nobody bet on its result.
+        #[pallet::constant]
+        type NoWinnerPolicy: Get<NoWinnerPolicy>;
+
+        /// Handler for the platform fees and the pots sent to the treasury.
+        type Treasury: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+        /// Part of every pot with winners taken as platform fee and sent to the `Treasury`.
+        #[pallet::constant]
+        type PlatformFee: Get<Permill>;
+
+        /// Max commission a creator can take from the pot of a match.
+        #[pallet::constant]
+        type MaxCreatorCommission: Get<Permill>;
+
+        /// Part of a cancelled or reduced bet paid to the creator of the match.
+        #[pallet::constant]
+        type CancellationFee: Get<Permill>;
+
+        /// Account receiving the platform fees and the pots sent to the treasury of the matches
+        /// bet in `Assets`.
+        #[pallet::constant]
+        type AssetTreasury: Get<Self::AccountId>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
-    pub trait ConfigHelper: Config {
-        fn account_id() -> AccountIdOf<Self>;
-    }
-
-    impl<T: Config> ConfigHelper for T {
-        /// The account ID of the betting pot.
-        ///
-        /// This actually does computation. If you need to keep using it, then make sure you cache the
-        /// value and only call this once.
-        #[inline(always)]
-        fn account_id() -> AccountIdOf<Self> {
-            Self::PalletId::get().into_account_truncating()
-        }
-    }
+    // Id that will be assigned to the next created match.
+    #[pallet::storage]
+    #[pallet::getter(fn next_match_id)]
+    pub type NextMatchId<T: Config> = StorageValue<_, MatchId, ValueQuery>;
 
     // Mapping of open matches.
     #[pallet::storage]
     #[pallet::getter(fn get_matches)]
-    pub type Matches<T: Config> = StorageMap<
+    pub type Matches<T: Config> = StorageMap<_, Twox64Concat, MatchId, MatchOf<T>, OptionQuery>;
+
+    // Bets placed on every match.
+    // (matchId, bettor, outcome -> bet)
+    #[pallet::storage]
+    #[pallet::getter(fn get_bet)]
+    pub type Bets<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Twox64Concat, MatchId>,
+            NMapKey<Twox64Concat, T::AccountId>,
+            NMapKey<Twox64Concat, OutcomeIndex>,
+        ),
+        Bet<BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    // Index of the open matches of every creator.
+    // (creator, matchId -> ())
+    #[pallet::storage]
+    pub type MatchesByCreator<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, MatchId, (), OptionQuery>;
+
+    // Disputes raised against the result of a match.
+    // (matchId -> [(disputer, bond)])
+    #[pallet::storage]
+    #[pallet::getter(fn get_disputes)]
+    pub type Disputes<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        MatchId,
+        BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxDisputes>,
+        ValueQuery,
+    >;
+
+    // Pots without winners waiting to be added to the next match of their creator in the same asset,
+    // and the match whose pot account holds them.
+    // (creator, asset -> (matchId, amount))
+    #[pallet::storage]
+    #[pallet::getter(fn get_rollover)]
+    pub type Rollovers<T: Config> = StorageDoubleMap<
         _,
         Twox64Concat,
         T::AccountId,
-        Match<T::BlockNumber, TeamName<T>, Bets<T>, BalanceOf<T>>,
+        Twox64Concat,
+        Option<AssetIdOf<T>>,
+        (MatchId, BalanceOf<T>),
         OptionQuery,
     >;
 
+    // Matches to finalise once a block is reached, walked by `on_idle`.
+    // (blockNumber, matchId -> ())
+    #[pallet::storage]
+    pub type SettlementQueue<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, MatchId, (), OptionQuery>;
+
+    // Next block of the settlement queue to be walked.
+    #[pallet::storage]
+    pub type SettlementCursor<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+    // Block at which the settlement of a match is scheduled.
+    // (matchId -> blockNumber)
+    #[pallet::storage]
+    #[pallet::getter(fn get_scheduled_settlement)]
+    pub type ScheduledSettlements<T: Config> =
+        StorageMap<_, Twox64Concat, MatchId, T::BlockNumber, OptionQuery>;
+
+    // Markets derived from a match, settled when its score is set.
+    // (matchId -> [marketId])
+    #[pallet::storage]
+    #[pallet::getter(fn get_derived_markets)]
+    pub type DerivedMarkets<T: Config> =
+        StorageMap<_, Twox64Concat, MatchId, BoundedVec<MatchId, T::MaxDerivedMarkets>, ValueQuery>;
+
+    // Odds and bond of the fixed-odds matches, the other matches are parimutuel.
+    // (matchId -> book)
+    #[pallet::storage]
+    #[pallet::getter(fn get_book)]
+    pub type Books<T: Config> = StorageMap<_, Twox64Concat, MatchId, BookOf<T>, OptionQuery>;
+
+    // Market makers of the matches traded in shares.
+    // (matchId -> market maker)
+    #[pallet::storage]
+    #[pallet::getter(fn get_market_maker)]
+    pub type MarketMakers<T: Config> =
+        StorageMap<_, Twox64Concat, MatchId, MarketMaker<BalanceOf<T>>, OptionQuery>;
+
+    // Matches that take bets while they are played.
+    // (matchId -> in-play state)
+    #[pallet::storage]
+    #[pallet::getter(fn get_in_play)]
+    pub type InPlayMarkets<T: Config> =
+        StorageMap<_, Twox64Concat, MatchId, InPlayOf<T>, OptionQuery>;
+
+    // Amount discounted from the position of a bettor on an outcome by its bets placed in-play.
+    // (matchId, bettor, outcome -> amount)
+    #[pallet::storage]
+    #[pallet::getter(fn get_in_play_discount)]
+    pub type InPlayDiscounts<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Twox64Concat, MatchId>,
+            NMapKey<Twox64Concat, T::AccountId>,
+            NMapKey<Twox64Concat, OutcomeIndex>,
+        ),
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    // Id that will be assigned to the next placed parlay.
+    #[pallet::storage]
+    #[pallet::getter(fn next_parlay_id)]
+    pub type NextParlayId<T: Config> = StorageValue<_, ParlayId, ValueQuery>;
+
+    // Parlays that are not settled yet.
+    // (parlayId -> parlay)
+    #[pallet::storage]
+    #[pallet::getter(fn get_parlay)]
+    pub type Parlays<T: Config> = StorageMap<_, Twox64Concat, ParlayId, ParlayOf<T>, OptionQuery>;
+
+    // Parlays with a leg on a match, settled when the match is.
+    // (matchId -> [parlayId])
+    #[pallet::storage]
+    #[pallet::getter(fn get_match_parlays)]
+    pub type MatchParlays<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        MatchId,
+        BoundedVec<ParlayId, T::MaxParlaysPerMatch>,
+        ValueQuery,
+    >;
+
+    // Id that will be assigned to the next registered team.
+    #[pallet::storage]
+    #[pallet::getter(fn next_team_id)]
+    pub type NextTeamId<T: Config> = StorageValue<_, TeamId, ValueQuery>;
+
+    // Registered teams.
+    // (teamId -> team)
+    #[pallet::storage]
+    #[pallet::getter(fn get_team)]
+    pub type Teams<T: Config> = StorageMap<_, Twox64Concat, TeamId, TeamOf<T>, OptionQuery>;
+
+    // Registered teams by their name, trimmed and lowercased so that the same team can not be
+    // registered twice.
+    // (name -> teamId)
+    #[pallet::storage]
+    #[pallet::getter(fn get_team_id)]
+    pub type TeamIds<T: Config> = StorageMap<_, Blake2_128Concat, TeamName<T>, TeamId, OptionQuery>;
+
+    // Id that will be assigned to the next created tournament.
+    #[pallet::storage]
+    #[pallet::getter(fn next_tournament_id)]
+    pub type NextTournamentId<T: Config> = StorageValue<_, TournamentId, ValueQuery>;
+
+    // Tournaments that have not finished yet.
+    // (tournamentId -> tournament)
+    #[pallet::storage]
+    #[pallet::getter(fn get_tournament)]
+    pub type Tournaments<T: Config> =
+        StorageMap<_, Twox64Concat, TournamentId, TournamentOf<T>, OptionQuery>;
+
+    // Tournament every match scheduled by a tournament belongs to.
+    // (matchId -> tournamentId)
+    #[pallet::storage]
+    #[pallet::getter(fn get_match_tournament)]
+    pub type TournamentMatches<T: Config> =
+        StorageMap<_, Twox64Concat, MatchId, TournamentId, OptionQuery>;
+
+    // Tournaments whose current round is over, advanced by `on_idle`.
+    // (tournamentId -> ())
+    #[pallet::storage]
+    pub type RoundsOver<T: Config> = StorageMap<_, Twox64Concat, TournamentId, (), OptionQuery>;
+
     // Mapping of all match hashes.
-    // (hash -> owner)
+    // (hash -> matchId)
     #[pallet::storage]
     #[pallet::getter(fn get_match_hashes)]
-    pub type MatchHashes<T: Config> =
-        StorageMap<_, Twox64Concat, T::Hash, T::AccountId, OptionQuery>;
+    pub type MatchHashes<T: Config> = StorageMap<_, Twox64Concat, T::Hash, MatchId, OptionQuery>;
 
     // Pallets use events to inform users when important changes are made.
     // https://docs.substrate.io/main-docs/build/events-errors/
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        /// A new match has been created. [who, team1, team2, start, length]
+        /// A new match has been created. [matchId, who, outcomes, start, length]
         MatchCreated(
+            MatchId,
             T::AccountId,
-            TeamName<T>,
-            TeamName<T>,
+            OutcomeNames<T>,
             T::BlockNumber,
             T::BlockNumber,
         ),
-        /// A new bet has been created. [matchId, who, amount, result]
-        BetPlaced(T::AccountId, T::AccountId, BalanceOf<T>, MatchResult),
-        /// A match result has been set. [matchId, result]
-        MatchResult(T::AccountId, MatchResult),
-    }
-
-    #[pallet::genesis_config]
-    pub struct GenesisConfig<T: Config> {
-        pub existential_deposit:  BalanceOf<T>,
-    }
-    #[cfg(feature = "std")]
-    impl<T: Config> Default for GenesisConfig<T> {
-        fn default() -> Self {
-            Self { existential_deposit: Default::default() }
-        }
-    }
-
-    #[pallet::genesis_build]
-    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
-        fn build(&self) {
-            T::Currency::deposit_creating(&T::account_id(), self.existential_deposit);
-            T::Currency::reserve(&T::account_id(),  self.existential_deposit).map_err(|_err| Error::<T>::NoExistentialDepositReserved).ok();
-        }
+        /// A new bet has been created. [matchId, who, amount, outcome]
+        BetPlaced(MatchId, T::AccountId, BalanceOf<T>, OutcomeIndex),
+        /// A bet has been cancelled or reduced before the match started. [matchId, who, amount, outcome, fee]
+        BetCancelled(
+            MatchId,
+            T::AccountId,
+            BalanceOf<T>,
+            OutcomeIndex,
+            BalanceOf<T>,
+        ),
+        /// A match result has been set. [matchId, outcome]
+        MatchResult(MatchId, OutcomeIndex),
+        /// A market has been derived from a match. [matchId, marketId, market]
+        MarketDerived(MatchId, MatchId, ScoreMarket),
+        /// The score of a match has been set. [matchId, score]
+        ScoreSet(MatchId, Score),
+        /// The odds of a fixed-odds match have been posted with its bond. [matchId, bond]
+        OddsPosted(MatchId, BalanceOf<T>),
+        /// The subsidy of the market maker of a match has been reserved. [matchId, subsidy]
+        SubsidyReserved(MatchId, BalanceOf<T>),
+        /// Shares of an outcome have been bought from a market maker. [matchId, who, outcome, shares, cost]
+        SharesBought(
+            MatchId,
+            T::AccountId,
+            OutcomeIndex,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Shares of an outcome have been sold to a market maker. [matchId, who, outcome, shares, proceeds]
+        SharesSold(
+            MatchId,
+            T::AccountId,
+            OutcomeIndex,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// A match takes bets while it is played. [matchId]
+        InPlayOpened(MatchId),
+        /// The betting on a match played in-play has been suspended. [matchId, until]
+        BettingSuspended(MatchId, T::BlockNumber),
+        /// The betting on a match played in-play has been resumed. [matchId]
+        BettingResumed(MatchId),
+        /// A parlay has been placed. [parlayId, who, stake, liability]
+        ParlayPlaced(ParlayId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+        /// Every leg of a parlay has won or been voided, and the bettor has been paid. [parlayId, who, winnings]
+        ParlayWon(ParlayId, T::AccountId, BalanceOf<T>),
+        /// A leg of a parlay has lost, and the stake has been paid to the bookmaker. [parlayId, who]
+        ParlayLost(ParlayId, T::AccountId),
+        /// A team has been registered. [teamId, name]
+        TeamRegistered(TeamId, TeamName<T>),
+        /// The name or the metadata of a team have been updated. [teamId, name]
+        TeamUpdated(TeamId, TeamName<T>),
+        /// A tournament has been created with its outright market. [tournamentId, organizer, outrightId]
+        TournamentCreated(TournamentId, T::AccountId, MatchId),
+        /// The matches of a round of a tournament have been scheduled. [tournamentId, round]
+        RoundScheduled(TournamentId, u32),
+        /// A tournament has finished, without a winner on a tie. [tournamentId, team]
+        TournamentFinished(TournamentId, Option<OutcomeIndex>),
+        /// A match has been voided and its bets will be refunded. [matchId]
+        MatchVoided(MatchId),
+        /// A match has been cancelled and its bets refunded. [matchId]
+        MatchCancelled(MatchId),
+        /// A match never got a result and its bets have been refunded. [matchId, who, incentive]
+        MatchTimedOut(MatchId, T::AccountId, BalanceOf<T>),
+        /// The settlement of a match has been scheduled. [matchId, when]
+        SettlementScheduled(MatchId, T::BlockNumber),
+        /// The result of a match has been disputed. [matchId, who]
+        ResultDisputed(MatchId, T::AccountId),
+        /// A disputed result has been upheld and the bonds slashed. [matchId]
+        ResultUpheld(MatchId),
+        /// A disputed result has been overturned and the bonds returned. [matchId, outcome]
+        ResultOverturned(MatchId, OutcomeIndex),
+        /// The winnings of a bettor have been claimed. [matchId, who, amount]
+        WinningsClaimed(MatchId, T::AccountId, BalanceOf<T>),
+        /// A settled match has been deleted. [matchId]
+        MatchClosed(MatchId),
+        /// Nobody won a match and the bets have been refunded. [matchId, amount]
+        PotRefunded(MatchId, BalanceOf<T>),
+        /// Nobody won a match and the pot goes to the next match of the creator. [matchId, creator, amount]
+        PotRolledOver(MatchId, T::AccountId, BalanceOf<T>),
+        /// Nobody won a match and the pot has been sent to the treasury. [matchId, amount]
+        PotSentToTreasury(MatchId, BalanceOf<T>),
+        /// The fees of a match have been taken before paying the winners. [matchId, pot, platformFee, commission]
+        MatchSettled(MatchId, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>),
     }
 
     // Errors inform users that something went wrong.
@@ -220,8 +1038,6 @@ pub mod pallet {
     pub enum Error<T> {
         /// The match to be created already exist.
         MatchAlreadyExists,
-        /// Each account can only have one match open.
-        OriginHasAlreadyOpenMatch,
         /// The time of the match is over.
         TimeMatchOver,
         /// The match where the bet is placed does not exist
@@ -230,162 +1046,560 @@ pub mod pallet {
         MatchHasStarted,
         /// The match has reach its betting limit
         MaxBets,
-        /// You already place the same bet in that match
-        AlreadyBet,
         /// No allowing set the result if the match not over
         TimeMatchNotOver,
         /// The match still has not a result set
         MatchNotResult,
         /// The team name is too long
         TeamNameTooLong,
-        /// Existential deposit can not be reserved at the beginning
-        NoExistentialDepositReserved,
+        /// There are no more match ids available
+        NoAvailableMatchId,
+        /// Only the creator of the match can perform this action
+        NotMatchCreator,
+        /// The result of the match has already been set
+        ResultAlreadySet,
+        /// The result of the match can still be disputed
+        DisputePeriodNotOver,
+        /// The result of the match can no longer be disputed
+        DisputePeriodOver,
+        /// The result of the match is being disputed
+        MatchDisputed,
+        /// You already disputed the result of that match
+        AlreadyDisputed,
+        /// The result of the match has reach its dispute limit
+        MaxDisputes,
+        /// The result of the match is not being disputed
+        NoDispute,
+        /// There are no winnings left to claim in that match
+        NothingToClaim,
+        /// The winners can still claim their winnings
+        ClaimPeriodNotOver,
+        /// The commission is higher than the allowed maximum
+        CommissionTooHigh,
+        /// The asset of the match does not exist
+        UnknownAsset,
+        /// You have no bet on that result of the match
+        NoBet,
+        /// The amount is higher than your bet
+        AmountExceedsBet,
+        /// The result of the match can still be set
+        ResultTimeoutNotOver,
+        /// The settlement of the match is already scheduled
+        SettlementAlreadyScheduled,
+        /// A match needs at least two outcomes
+        TooFewOutcomes,
+        /// The match has more outcomes than allowed
+        TooManyOutcomes,
+        /// The match has no outcome with that index
+        UnknownOutcome,
+        /// Markets can only be derived from a two-team match
+        NotTwoTeamMatch,
+        /// The match has reach its limit of derived markets
+        TooManyDerivedMarkets,
+        /// The match is not settled from its score
+        NotScoreMarket,
+        /// The number of odds is not the number of outcomes
+        OddsMismatch,
+        /// The odds of an outcome are not higher than one
+        InvalidOdds,
+        /// The bond does not cover the winnings of the bets at the posted odds
+        InsufficientBond,
+        /// The subsidy is too low to fund a market maker
+        SubsidyTooLow,
+        /// The match has no market maker
+        NoMarketMaker,
+        /// The match is traded in shares with its market maker
+        MarketMakerMatch,
+        /// The price of the trade is beyond its limit
+        SlippageExceeded,
+        /// The match already takes bets while it is played
+        AlreadyInPlay,
+        /// The match does not take bets while it is played
+        NotInPlay,
+        /// Fixed odds can not be bet while the match is played
+        FixedOddsInPlay,
+        /// The betting on the match is suspended
+        BettingSuspended,
+        /// The betting can not be suspended for that long
+        SuspensionTooLong,
+        /// A parlay needs at least two legs
+        TooFewLegs,
+        /// The parlay has more legs than allowed
+        TooManyLegs,
+        /// The parlay has two legs on the same match
+        DuplicateLeg,
+        /// The match of a leg does not have fixed odds
+        NotFixedOdds,
+        /// The matches of the legs are not from the same bookmaker
+        MixedBookmakers,
+        /// The match has reach its limit of parlays
+        TooManyParlays,
+        /// There are no more parlay ids available
+        NoAvailableParlayId,
+        /// The parlay does not exist
+        ParlayDoesNotExist,
+        /// Some legs of the parlay are not settled yet
+        ParlayNotSettled,
+        /// The number of teams of a knockout is not a power of two
+        BracketNotPowerOfTwo,
+        /// The tournament has more matches than allowed
+        TooManyMatches,
+        /// The matches are longer than the rounds
+        RoundTooShort,
+        /// There are no more tournament ids available
+        NoAvailableTournamentId,
+        /// The tournament does not exist
+        TournamentDoesNotExist,
+        /// Some matches of the round have no final result yet
+        RoundNotOver,
+        /// The team is not registered
+        UnknownTeam,
+        /// A team with the same name is already registered
+        TeamAlreadyRegistered,
+        /// The team name is blank
+        EmptyTeamName,
+        /// The team metadata is too long
+        TeamMetadataTooLong,
+        /// There are no more team ids available
+        NoAvailableTeamId,
+        /// The same team is listed twice
+        DuplicateTeam,
+        /// Some winnings of the match have already been claimed
+        WinningsAlreadyClaimed,
+        /// The match is over or has a result
+        MatchNotInPlay,
+        /// The amount is zero
+        ZeroAmount,
+        /// The market maker can not price the shares
+        NoQuote,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Settles or refunds the queued matches that can be finalised with the weight left in
+        /// the block, then advances the tournaments whose round is over.
+        fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let consumed = Self::finalise_queued(now, remaining_weight);
+            consumed.saturating_add(Self::advance_rounds_over(
+                remaining_weight.saturating_sub(consumed),
+            ))
+        }
+
+        fn integrity_test() {
+            // Every match settled from its result has both teams and the draw as outcomes
+            assert!(
+                T::MaxOutcomes::get() >= 3,
+                "`MaxOutcomes` must hold both teams and the draw"
+            );
+            assert!(
+                T::MaxTeamNameLength::get() as usize >= DRAW.len(),
+                "`MaxTeamNameLength` must hold the name of the draw"
+            );
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Create a new match to bet on.
+        /// Register a team, so that matches can be created between it and other teams.
+        /// The dispatch origin for this call must be the `TeamOrigin`.
+        /// Names are compared trimmed and lowercased, so that a team is only registered once.
+        ///
+        /// Emit an event on success: `TeamRegistered`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be the `TeamOrigin`.
+        ///   * `name` – Name displayed as the outcome of the matches of the team.
+        ///   * `metadata` – Free-form details about the team.
+        ///
+        /// **Errors:**
+        ///   * `TeamNameTooLong` – The name is longer than `MaxTeamNameLength`.
+        ///   * `EmptyTeamName` – The name is blank.
+        ///   * `TeamMetadataTooLong` – The metadata is longer than `MaxTeamMetadataLength`.
+        ///   * `TeamAlreadyRegistered` – A team with the same name is already registered.
+        ///   * `NoAvailableTeamId` – The team id counter has overflowed.
+        #[pallet::weight(T::WeightInfo::register_team())]
+        pub fn register_team(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            metadata: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            T::TeamOrigin::ensure_origin(origin)?;
+
+            let team = Self::new_team(name, metadata)?;
+            let team_id = Self::insert_team(team.clone())?;
+
+            Self::deposit_event(Event::TeamRegistered(team_id, team.name));
+            Ok(())
+        }
+
+        /// Update the name and the metadata of a registered team. The matches already created
+        /// keep the name of the team when they were created.
+        /// The dispatch origin for this call must be the `TeamOrigin`.
+        ///
+        /// Emit an event on success: `TeamUpdated`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be the `TeamOrigin`.
+        ///   * `team_id` – Id of the team.
+        ///   * `name` – Name displayed as the outcome of the matches of the team.
+        ///   * `metadata` – Free-form details about the team.
+        ///
+        /// **Errors:**
+        ///   * `UnknownTeam` – The team is not registered.
+        ///   * `TeamNameTooLong` – The name is longer than `MaxTeamNameLength`.
+        ///   * `EmptyTeamName` – The name is blank.
+        ///   * `TeamMetadataTooLong` – The metadata is longer than `MaxTeamMetadataLength`.
+        ///   * `TeamAlreadyRegistered` – Another team with the same name is registered.
+        #[pallet::weight(T::WeightInfo::update_team())]
+        pub fn update_team(
+            origin: OriginFor<T>,
+            team_id: TeamId,
+            name: Vec<u8>,
+            metadata: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            T::TeamOrigin::ensure_origin(origin)?;
+
+            let old = <Teams<T>>::get(team_id).ok_or(Error::<T>::UnknownTeam)?;
+            let team = Self::new_team(name, metadata)?;
+            let key = Self::team_key(&team.name)?;
+            ensure!(
+                <TeamIds<T>>::get(&key).map_or(true, |id| id == team_id),
+                Error::<T>::TeamAlreadyRegistered
+            );
+
+            <TeamIds<T>>::remove(Self::team_key(&old.name)?);
+            <TeamIds<T>>::insert(key, team_id);
+            <Teams<T>>::insert(team_id, team.clone());
+
+            Self::deposit_event(Event::TeamUpdated(team_id, team.name));
+            Ok(())
+        }
+
+        /// Create a new match between two registered teams to bet on, with the outcomes named
+        /// after `team1`, `team2` and `Draw` in the order of `MatchResult`. There can only be one
+        /// match between the same teams starting at the same time.
         /// Emit an event on success: `MatchCreated`.
         ///
         /// **Parameters:**
         ///   * `origin` – Origin for the call. Must be signed.
-        ///   * `team1` – Name of the first team.
-        ///   * `team2` – Name of the second team.
+        ///   * `team1` – Id of the first team.
+        ///   * `team2` – Id of the second team.
         ///   * `start` – Time when the match starts and bets can be placed (in blocks).
         ///   * `length` – Duration of the match (in blocks).
+        ///   * `commission` – Part of the pot taken by the creator when the match is settled.
+        ///   * `asset` – Asset the match is bet in, the native currency if `None`.
         ///
         /// **Errors:**
-        ///   * `MatchAlreadyExists` – A match for the specified values already exists.
-        ///   * `OriginHasAlreadyOpenMatch` – An origin can only have one match open.
+        ///   * `UnknownTeam` – A team is not registered.
+        ///   * `DuplicateTeam` – The teams are the same.
+        ///   * `MatchAlreadyExists` – A match between the teams starting at that time already
+        ///     exists.
         ///   * `TimeMatchOver` – The match is created when the match time is over.
+        ///   * `NoAvailableMatchId` – The match id counter has overflowed.
+        ///   * `CommissionTooHigh` – The commission is higher than `MaxCreatorCommission`.
+        ///   * `UnknownAsset` – The asset does not exist.
         #[pallet::weight(T::WeightInfo::create_match_to_bet())]
         pub fn create_match_to_bet(
             origin: OriginFor<T>,
-            team1: Vec<u8>,
-            team2: Vec<u8>,
+            team1: TeamId,
+            team2: TeamId,
             start: T::BlockNumber,
             length: T::BlockNumber,
+            commission: Permill,
+            asset: Option<AssetIdOf<T>>,
         ) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer.
             // This function will return an error if the extrinsic is not signed.
             // https://docs.substrate.io/main-docs/build/origins/
             let who = ensure_signed(origin)?;
+            ensure!(team1 != team2, Error::<T>::DuplicateTeam);
 
-            // Check account has no open match
-            ensure!(
-                !<Matches<T>>::contains_key(&who),
-                Error::<T>::OriginHasAlreadyOpenMatch
-            );
+            let mut names = Self::team_names(&[team1, team2])?;
+            names.push(DRAW.to_vec());
+            let (mut betting_match, names) =
+                Self::new_match(who, names, start, length, commission, asset)?;
+            betting_match.teams = Some((team1, team2));
+            betting_match.market = Some(ScoreMarket::MatchResult);
+            Self::insert_match(betting_match, names, true, None)?;
+            Ok(())
+        }
 
-            // Check if start and length are valid
-            let current_block_number = <frame_system::Pallet<T>>::block_number();
+        /// Create a new market to bet on, with a list of named outcomes such as the horses of a
+        /// race. Bets and results refer to an outcome by its index in the list.
+        /// Emit an event on success: `MatchCreated`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `outcomes` – Names of the possible outcomes.
+        ///   * `start` – Time when the match starts and bets can be placed (in blocks).
+        ///   * `length` – Duration of the match (in blocks).
+        ///   * `commission` – Part of the pot taken by the creator when the match is settled.
+        ///   * `asset` – Asset the match is bet in, the native currency if `None`.
+        ///
+        /// **Errors:**
+        ///   * `MatchAlreadyExists` – A match for the specified values already exists.
+        ///   * `TimeMatchOver` – The match is created when the match time is over.
+        ///   * `NoAvailableMatchId` – The match id counter has overflowed.
+        ///   * `CommissionTooHigh` – The commission is higher than `MaxCreatorCommission`.
+        ///   * `UnknownAsset` – The asset does not exist.
+        ///   * `TooFewOutcomes` – There are less than two outcomes.
+        ///   * `TooManyOutcomes` – There are more than `MaxOutcomes` outcomes.
+        #[pallet::weight(T::WeightInfo::create_market())]
+        pub fn create_market(
+            origin: OriginFor<T>,
+            outcomes: Vec<Vec<u8>>,
+            start: T::BlockNumber,
+            length: T::BlockNumber,
+            commission: Permill,
+            asset: Option<AssetIdOf<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let (betting_match, names) =
+                Self::new_match(who, outcomes, start, length, commission, asset)?;
+            Self::insert_match(betting_match, names, true, None)?;
+            Ok(())
+        }
+
+        /// Create a fixed-odds market, where the creator is the bookmaker: a winning bet is paid
+        /// its stake times the decimal odds posted on its outcome, the winnings coming out of a
+        /// bond reserved from the creator, who keeps the losing bets. The market is bet in the
+        /// native currency and the creator takes no commission.
+        /// Emit an event on success: `MatchCreated` and `OddsPosted`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `outcomes` – Names of the possible outcomes.
+        ///   * `odds` – Decimal odds of every outcome, in the order of the outcomes.
+        ///   * `bond` – Amount reserved to pay the winnings.
+        ///   * `start` – Time when the match starts and bets can be placed (in blocks).
+        ///   * `length` – Duration of the match (in blocks).
+        ///
+        /// **Errors:**
+        ///   * `MatchAlreadyExists` – A match for the specified values already exists.
+        ///   * `TimeMatchOver` – The match is created when the match time is over.
+        ///   * `NoAvailableMatchId` – The match id counter has overflowed.
+        ///   * `TooFewOutcomes` – There are less than two outcomes.
+        ///   * `TooManyOutcomes` – There are more than `MaxOutcomes` outcomes.
+        ///   * `OddsMismatch` – The number of odds is not the number of outcomes.
+        ///   * `InvalidOdds` – The odds of an outcome are not higher than one.
+        #[pallet::weight(T::WeightInfo::create_fixed_odds_market())]
+        pub fn create_fixed_odds_market(
+            origin: OriginFor<T>,
+            outcomes: Vec<Vec<u8>>,
+            odds: Vec<FixedU128>,
+            bond: BalanceOf<T>,
+            start: T::BlockNumber,
+            length: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (betting_match, names) =
+                Self::new_match(who.clone(), outcomes, start, length, Permill::zero(), None)?;
+            ensure!(odds.len() == names.len(), Error::<T>::OddsMismatch);
             ensure!(
-                current_block_number < (start + length),
-                Error::<T>::TimeMatchOver
+                odds.iter()
+                    .all(|outcome_odds| *outcome_odds > FixedU128::one()),
+                Error::<T>::InvalidOdds
             );
+            let book = Book {
+                odds: odds.try_into().map_err(|_| Error::<T>::OddsMismatch)?,
+                bond,
+            };
+            let match_id = Self::insert_match(betting_match, names, false, None)?;
+
+            // Reserve the bond that pays the winnings at the posted odds
+            T::Currency::reserve(&who, bond)?;
+            <Books<T>>::insert(match_id, book);
+            Self::deposit_event(Event::OddsPosted(match_id, bond));
+            Ok(())
+        }
 
-            let team1_bounded_name: BoundedVec<_, T::MaxTeamNameLength> =
-                team1.try_into().map_err(|_| Error::<T>::TeamNameTooLong)?;
+        /// Create a market traded in shares with an automated market maker, which quotes the
+        /// price of the shares of every outcome with the logarithmic market scoring rule. Once
+        /// the result is final every share of the winning outcome is redeemed for one unit.
+        /// The subsidy is reserved from the creator to fund the loss of the market maker, the
+        /// higher it is the less a trade moves the prices. The market is bet in the native
+        /// currency and the creator takes no commission.
+        /// Emit an event on success: `MatchCreated` and `SubsidyReserved`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `outcomes` – Names of the possible outcomes.
+        ///   * `subsidy` – Amount reserved to fund the market maker.
+        ///   * `start` – Time when the match starts and shares can be traded (in blocks).
+        ///   * `length` – Duration of the match (in blocks).
+        ///
+        /// **Errors:**
+        ///   * `MatchAlreadyExists` – A match for the specified values already exists.
+        ///   * `TimeMatchOver` – The match is created when the match time is over.
+        ///   * `NoAvailableMatchId` – The match id counter has overflowed.
+        ///   * `TooFewOutcomes` – There are less than two outcomes.
+        ///   * `TooManyOutcomes` – There are more than `MaxOutcomes` outcomes.
+        ///   * `SubsidyTooLow` – The subsidy is too low to fund a market maker.
+        #[pallet::weight(T::WeightInfo::create_amm_market())]
+        pub fn create_amm_market(
+            origin: OriginFor<T>,
+            outcomes: Vec<Vec<u8>>,
+            subsidy: BalanceOf<T>,
+            start: T::BlockNumber,
+            length: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-            let team2_bounded_name: BoundedVec<_, T::MaxTeamNameLength> =
-                team2.try_into().map_err(|_| Error::<T>::TeamNameTooLong)?;
+            let (betting_match, names) =
+                Self::new_match(who.clone(), outcomes, start, length, Permill::zero(), None)?;
+            let liquidity: BalanceOf<T> =
+                lmsr::liquidity(subsidy.saturated_into(), names.len() as u32).saturated_into();
+            ensure!(!liquidity.is_zero(), Error::<T>::SubsidyTooLow);
+            let match_id = Self::insert_match(betting_match, names, false, None)?;
 
-            // Create the betting match
-            let betting_match = Match {
-                start,
-                length,
-                team1: team1_bounded_name.clone(),
-                team2: team2_bounded_name.clone(),
-                result: None,
-                bets: Default::default(),
-                deposit: T::MatchDeposit::get(),
-            };
+            // Reserve the subsidy that funds the loss of the market maker
+            T::Currency::reserve(&who, subsidy)?;
+            <MarketMakers<T>>::insert(match_id, MarketMaker { liquidity, subsidy });
+            Self::deposit_event(Event::SubsidyReserved(match_id, subsidy));
+            Ok(())
+        }
 
-            let match_hash = Self::get_match_hash(betting_match.clone());
+        /// Create a market derived from a two-team match, with its own pot, settled from the
+        /// score of the match. It has the same start, length and asset as the match.
+        /// Emit an event on success: `MatchCreated` and `MarketDerived`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed by the creator of the match.
+        ///   * `match_id` – Id of the two-team match.
+        ///   * `market` – How the market is settled from the score.
+        ///   * `commission` – Part of the pot taken by the creator when the market is settled.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NotMatchCreator` – The origin is not the creator of the match.
+        ///   * `NotTwoTeamMatch` – The match is not a two-team match created by
+        ///     `create_match_to_bet`.
+        ///   * `TooManyDerivedMarkets` – The match has `MaxDerivedMarkets` derived markets.
+        ///   * `TooManyOutcomes` – The market has more than `MaxOutcomes` outcomes.
+        ///   * `MatchAlreadyExists` – The same market has already been derived from the match.
+        ///   * `TimeMatchOver` – The match is over.
+        ///   * `CommissionTooHigh` – The commission is higher than `MaxCreatorCommission`.
+        #[pallet::weight(T::WeightInfo::create_derived_market())]
+        pub fn create_derived_market(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            market: ScoreMarket,
+            commission: Permill,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-            // Check if match already exists by checking its specs hash.
+            let parent = <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(parent.creator == who, Error::<T>::NotMatchCreator);
             ensure!(
-                !<MatchHashes<T>>::contains_key(&match_hash),
-                Error::<T>::MatchAlreadyExists
+                parent.market == Some(ScoreMarket::MatchResult) && parent.parent.is_none(),
+                Error::<T>::NotTwoTeamMatch
+            );
+            ensure!(
+                market
+                    .outcome_count()
+                    .map_or(false, |count| count <= T::MaxOutcomes::get()),
+                Error::<T>::TooManyOutcomes
             );
 
-            // Reserve the deposit
-            T::Currency::reserve(&who, T::MatchDeposit::get())?;
-
-            // Store the match hash with its creator account.
-            <MatchHashes<T>>::insert(&match_hash, who.clone());
-
-            // Store the betting match in the list of open matches
-            <Matches<T>>::insert(&who, betting_match);
-
-            // Emit an event.
-            Self::deposit_event(Event::MatchCreated(
+            let team = |index: usize| {
+                parent
+                    .outcomes
+                    .get(index)
+                    .map(|outcome| outcome.name.to_vec())
+                    .unwrap_or_default()
+            };
+            let names = market.outcome_names(&team(0), &team(1));
+            let (mut betting_match, names) = Self::new_match(
                 who,
-                team1_bounded_name,
-                team2_bounded_name,
-                start,
-                length,
-            ));
+                names,
+                parent.start,
+                parent.length,
+                commission,
+                parent.asset,
+            )?;
+            betting_match.market = Some(market);
+            betting_match.parent = Some(match_id);
+            let market_id = Self::insert_match(betting_match, names, true, None)?;
+            <DerivedMarkets<T>>::try_append(match_id, market_id)
+                .map_err(|_| Error::<T>::TooManyDerivedMarkets)?;
 
-            // Return a successful DispatchResult
+            Self::deposit_event(Event::MarketDerived(match_id, market_id, market));
             Ok(())
         }
 
         /// Create bet for a match.
+        /// Betting again on the same result adds the amount to the position of the origin on it.
         /// Emit an event on success: `BetPlaced`.
         ///
         /// **Parameters:**
         ///   * `origin` – Origin for the call. Must be signed.
-        ///   * `match_id` – Id of the match, in our case the creator of the bet accountId .
+        ///   * `match_id` – Id of the match.
         ///   * `amount_to_bet` – Amount placed for the bet.
-        ///   * `result` – The result for the bet.
+        ///   * `result` – Index of the outcome the bet is on.
         ///
         /// **Errors:**
         ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
-        ///   * `MatchHasStarted` – If the match has started, betting is not allowed.
-        ///   * `TimeMatchOver` – The match is created when the match time is over.
-        ///   * `MaxBets`   - The match has reach its betting limit.
-        ///   * `AlreadyBet`   - You already place the same bet in that match.
+        ///   * `MatchHasStarted` – If the match has started, betting is not allowed unless it
+        ///     is played in-play.
+        ///   * `TimeMatchOver` – The match is over.
+        ///   * `BettingSuspended` – The betting on the match played in-play is suspended.
+        ///   * `MaxBets`   - The match has reach its limit of positions.
+        ///   * `UnknownOutcome` – The match has no outcome with that index.
+        ///   * `InsufficientBond` – The bond of a fixed-odds match does not cover the winnings of
+        ///     the bets on the outcome.
+        ///   * `MarketMakerMatch` – The match is traded in shares with its market maker.
+        ///   * `ZeroAmount` – The amount bet is zero.
         #[pallet::weight(T::WeightInfo::bet())]
         pub fn bet(
             origin: OriginFor<T>,
-            match_id: T::AccountId,
+            match_id: MatchId,
             amount_to_bet: BalanceOf<T>,
-            result: MatchResult,
+            result: OutcomeIndex,
         ) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer
             let who = ensure_signed(origin)?;
+            ensure!(!amount_to_bet.is_zero(), Error::<T>::ZeroAmount);
 
             // Find the match that user wants to place the bet
             let mut match_to_bet =
-                <Matches<T>>::get(&match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
-
-            let current_block_number = <frame_system::Pallet<T>>::block_number();
+                <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(match_to_bet.has_outcome(result), Error::<T>::UnknownOutcome);
             ensure!(
-                current_block_number < match_to_bet.start,
-                Error::<T>::MatchHasStarted
+                !<MarketMakers<T>>::contains_key(match_id),
+                Error::<T>::MarketMakerMatch
             );
 
-            // Create the bet to be placed
-            let bet = Bet {
-                bettor: who.clone(),
-                amount: amount_to_bet.clone(),
-                result,
-            };
-
-            match match_to_bet.bets.binary_search(&bet) {
-                Ok(_pos) => return Err(Error::<T>::AlreadyBet.into()),
-                Err(pos) => match_to_bet
-                    .bets
-                    .try_insert(pos, bet.clone())
-                    .map_err(|_| Error::<T>::MaxBets)?,
-            }
+            let in_play = Self::ensure_betting_open(match_id, &match_to_bet)?;
 
             // Check user has enough funds and send it to the betting pallet account
-            T::Currency::transfer(&who, &T::account_id(), amount_to_bet, AllowDeath)?;
+            Self::transfer_asset(
+                match_to_bet.asset,
+                &who,
+                &Self::pot_account(match_id),
+                amount_to_bet,
+            )?;
 
-            // Store the betting match in the list of open matches
-            <Matches<T>>::insert(&match_id, match_to_bet);
+            Self::add_to_position(match_id, &mut match_to_bet, &who, result, amount_to_bet)?;
+
+            // A bet placed in-play only shares the winnings for the part of the match left
+            if let Some(mut in_play) = in_play {
+                let discount = Self::in_play_discount(&match_to_bet, amount_to_bet);
+                <InPlayDiscounts<T>>::mutate((match_id, &who, result), |total| *total += discount);
+                if let Some(total) = in_play.discounts.get_mut(result as usize) {
+                    *total += discount;
+                }
+                <InPlayMarkets<T>>::insert(match_id, in_play);
+            }
+
+            // The bookmaker must be able to pay every bet on the outcome if it wins
+            if let Some(book) = <Books<T>>::get(match_id) {
+                ensure!(
+                    book.winnings(result, match_to_bet.total_on(result)) <= book.bond,
+                    Error::<T>::InsufficientBond
+                );
+            }
+            <Matches<T>>::insert(match_id, match_to_bet);
 
             // Emit an event.
             Self::deposit_event(Event::BetPlaced(match_id, who, amount_to_bet, result));
@@ -394,121 +1608,2746 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Set the result of an existing match.
-        /// The dispatch origin for this call must be _Root_.
-        ///
-        /// Emit an event on success: `MatchResult`.
+        /// Buy shares of an outcome of a match from its market maker, at the price it quotes.
+        /// Every share of the winning outcome is redeemed for one unit once the result is final.
+        /// Emit an event on success: `SharesBought`.
         ///
         /// **Parameters:**
-        ///   * `origin` – Origin for the call. Must be _Root_.
-        ///   * `match_id` – Id of the match, in our case the creator of the bet accountId .
-        ///   * `result` – The result of match.
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///   * `outcome` – Index of the outcome.
+        ///   * `shares` – Number of shares bought.
+        ///   * `max_cost` – Highest amount the origin is willing to pay for the shares.
         ///
         /// **Errors:**
         ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
-        ///   * `TimeMatchNotOver` – If the match is not over, set the result is not allowed.
-        #[pallet::weight(T::WeightInfo::set_result())]
-        pub fn set_result(
+        ///   * `NoMarketMaker` – The match has no market maker.
+        ///   * `UnknownOutcome` – The match has no outcome with that index.
+        ///   * `MatchHasStarted` – If the match has started, trading is not allowed unless it
+        ///     is played in-play.
+        ///   * `TimeMatchOver` – The match is over.
+        ///   * `BettingSuspended` – The trading on the match played in-play is suspended.
+        ///   * `SlippageExceeded` – The shares cost more than `max_cost`.
+        ///   * `MaxBets` – The match has reach its limit of positions.
+        ///   * `ZeroAmount` – The number of shares is zero.
+        #[pallet::weight(T::WeightInfo::buy_shares())]
+        pub fn buy_shares(
             origin: OriginFor<T>,
-            match_id: T::AccountId,
-            match_result: MatchResult,
+            match_id: MatchId,
+            outcome: OutcomeIndex,
+            shares: BalanceOf<T>,
+            max_cost: BalanceOf<T>,
         ) -> DispatchResult {
-            // Only root can call this extrinsic.
-            ensure_root(origin)?;
+            let who = ensure_signed(origin)?;
+            ensure!(!shares.is_zero(), Error::<T>::ZeroAmount);
+            let (mut betting_match, maker) = Self::ensure_trading(match_id, outcome)?;
 
-            //Find the match where user wants to place the bet
-            let mut match_to_set_result =
-                <Matches<T>>::take(&match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            let cost = Self::buy_cost(&betting_match, &maker, outcome, shares)
+                .filter(|cost| *cost <= max_cost)
+                .ok_or(Error::<T>::SlippageExceeded)?;
 
-            // Check if start and length are valid
-            let current_block_number = <frame_system::Pallet<T>>::block_number();
-            ensure!(
-                current_block_number > (match_to_set_result.start + match_to_set_result.length),
-                Error::<T>::TimeMatchNotOver
-            );
+            T::Currency::transfer(&who, &Self::pot_account(match_id), cost, AllowDeath)?;
+            Self::add_to_position(match_id, &mut betting_match, &who, outcome, shares)?;
+            <Matches<T>>::insert(match_id, betting_match);
 
-            match_to_set_result.result = Some(match_result.clone());
+            Self::deposit_event(Event::SharesBought(match_id, who, outcome, shares, cost));
+            Ok(())
+        }
 
-            // Store the updated match result
-            <Matches<T>>::insert(&match_id, match_to_set_result);
+        /// Sell shares of an outcome of a match back to its market maker, at the price it
+        /// quotes.
+        /// Emit an event on success: `SharesSold`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///   * `outcome` – Index of the outcome.
+        ///   * `shares` – Number of shares sold.
+        ///   * `min_proceeds` – Lowest amount the origin is willing to be paid for the shares.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NoMarketMaker` – The match has no market maker.
+        ///   * `UnknownOutcome` – The match has no outcome with that index.
+        ///   * `MatchHasStarted` – If the match has started, trading is not allowed unless it
+        ///     is played in-play.
+        ///   * `TimeMatchOver` – The match is over.
+        ///   * `BettingSuspended` – The trading on the match played in-play is suspended.
+        ///   * `NoBet` – The origin has no shares of that outcome.
+        ///   * `AmountExceedsBet` – The origin has less shares of that outcome.
+        ///   * `SlippageExceeded` – The shares are paid less than `min_proceeds`.
+        #[pallet::weight(T::WeightInfo::sell_shares())]
+        pub fn sell_shares(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            outcome: OutcomeIndex,
+            shares: BalanceOf<T>,
+            min_proceeds: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let (mut betting_match, maker) = Self::ensure_trading(match_id, outcome)?;
 
-            // Emit an event.
-            Self::deposit_event(Event::MatchResult(match_id, match_result));
+            let outstanding = Self::outstanding_shares(&betting_match);
+            Self::take_from_position(match_id, &mut betting_match, &who, outcome, Some(shares))?;
+            let proceeds = lmsr::sell_proceeds(
+                &outstanding,
+                maker.liquidity.saturated_into(),
+                outcome as usize,
+                shares.saturated_into(),
+            )
+            .map(|proceeds| proceeds.saturated_into())
+            .filter(|proceeds| *proceeds >= min_proceeds)
+            .ok_or(Error::<T>::SlippageExceeded)?;
 
-            // Return a successful DispatchResult
+            T::Currency::transfer(&Self::pot_account(match_id), &who, proceeds, AllowDeath)?;
+            <Matches<T>>::insert(match_id, betting_match);
+
+            Self::deposit_event(Event::SharesSold(match_id, who, outcome, shares, proceeds));
             Ok(())
         }
 
-        /// When a match ends the owner of the match can distribute funds to the winners and delete the match.
+        /// Cancel the bet of the origin on a result of a match that has not started.
+        /// The `CancellationFee` is paid to the creator and the rest is refunded.
+        /// Emit an event on success: `BetCancelled`.
         ///
         /// **Parameters:**
         ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///   * `result` – Index of the outcome the bet is on.
         ///
         /// **Errors:**
         ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
-        ///   * `MatchNotResult` – The match still has not a result.
-        #[pallet::weight(T::WeightInfo::distribute_winnings())]
-        pub fn distribute_winnings(origin: OriginFor<T>) -> DispatchResult {
-            // Check that the extrinsic was signed and get the signer.
+        ///   * `MatchHasStarted` – If the match has started, cancelling a bet is not allowed.
+        ///   * `NoBet` – The origin has no bet on that result.
+        ///   * `MarketMakerMatch` – The match is traded in shares with its market maker.
+        #[pallet::weight(T::WeightInfo::cancel_bet())]
+        pub fn cancel_bet(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            result: OutcomeIndex,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_reduce_bet(match_id, who, result, None)
+        }
+
+        /// Reduce the bet of the origin on a result of a match that has not started.
+        /// The `CancellationFee` is paid to the creator and the rest is refunded.
+        /// Emit an event on success: `BetCancelled`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///   * `result` – Index of the outcome the bet is on.
+        ///   * `amount` – Amount taken off the bet.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `MatchHasStarted` – If the match has started, reducing a bet is not allowed.
+        ///   * `NoBet` – The origin has no bet on that result.
+        ///   * `AmountExceedsBet` – The amount is higher than the bet.
+        ///   * `MarketMakerMatch` – The match is traded in shares with its market maker.
+        #[pallet::weight(T::WeightInfo::reduce_bet())]
+        pub fn reduce_bet(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            result: OutcomeIndex,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            Self::do_reduce_bet(match_id, who, result, Some(amount))
+        }
 
-            // Get the match that user wants to close, deleting it
-            let mut match_to_bet = <Matches<T>>::take(&who).ok_or(Error::<T>::MatchDoesNotExist)?;
+        /// Opt in to keep taking bets on a match while it is played, until it ends. A bet placed
+        /// in-play only shares the winnings for the part of the match left when it is placed,
+        /// so that late bettors do not profit from what they have already seen. The shares of a
+        /// market maker keep trading at the prices it quotes.
+        /// Emit an event on success: `InPlayOpened`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed by the creator of the match.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NotMatchCreator` – The origin is not the creator of the match.
+        ///   * `MatchHasStarted` – The match has already started.
+        ///   * `FixedOddsInPlay` – The match has fixed odds.
+        ///   * `AlreadyInPlay` – The match already takes bets while it is played.
+        #[pallet::weight(T::WeightInfo::open_in_play())]
+        pub fn open_in_play(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-            ensure!(match_to_bet.result.is_some(), Error::<T>::MatchNotResult);
+            let betting_match = <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(betting_match.creator == who, Error::<T>::NotMatchCreator);
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number < betting_match.start,
+                Error::<T>::MatchHasStarted
+            );
+            ensure!(
+                !<Books<T>>::contains_key(match_id),
+                Error::<T>::FixedOddsInPlay
+            );
+            ensure!(
+                !<InPlayMarkets<T>>::contains_key(match_id),
+                Error::<T>::AlreadyInPlay
+            );
 
-            let match_hash = Self::get_match_hash(match_to_bet.clone());
-            <MatchHashes<T>>::take(match_hash).ok_or(Error::<T>::MatchDoesNotExist)?;
+            let discounts = vec![Zero::zero(); betting_match.outcomes.len()];
+            <InPlayMarkets<T>>::insert(
+                match_id,
+                InPlay {
+                    suspended_until: None,
+                    discounts: discounts
+                        .try_into()
+                        .map_err(|_| Error::<T>::TooManyOutcomes)?,
+                },
+            );
 
-            // Iterate over all bets
-            let mut total_winners: BalanceOf<T> = 0u32.into();
-            let mut total_bet: BalanceOf<T> = 0u32.into();
-            let mut winners = Vec::new();
-            for bet in match_to_bet.bets.iter_mut() {
-                total_bet += bet.amount;
-                if Some(bet.result) == match_to_bet.result {
-                    total_winners += bet.amount;
-                    winners.push(bet)
-                }
-            }
+            Self::deposit_event(Event::InPlayOpened(match_id));
+            Ok(())
+        }
 
-            // Distribute funds
-            for winner_bet in &winners {
-                let weighted = Perbill::from_rational(winner_bet.amount, total_winners);
-                let amount_won = weighted * total_bet;
-                T::Currency::transfer(
-                    &T::account_id(),
-                    &winner_bet.bettor,
-                    amount_won,
-                    AllowDeath,
-                )?;
-            }
+        /// Suspend the betting on a match played in-play for a number of blocks, around a key
+        /// event of the match.
+        /// The dispatch origin for this call must be the `ResultOrigin`.
+        ///
+        /// Emit an event on success: `BettingSuspended`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be the `ResultOrigin`.
+        ///   * `match_id` – Id of the match.
+        ///   * `blocks` – Number of blocks the betting is suspended for.
+        ///
+        /// **Errors:**
+        ///   * `NotInPlay` – The match does not take bets while it is played.
+        ///   * `MatchNotInPlay` – The match is over or has a result.
+        ///   * `SuspensionTooLong` – The number of blocks is higher than `MaxSuspension`.
+        #[pallet::weight(T::WeightInfo::suspend_betting())]
+        pub fn suspend_betting(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            blocks: T::BlockNumber,
+        ) -> DispatchResult {
+            T::ResultOrigin::ensure_origin(origin)?;
+            ensure!(
+                blocks <= T::MaxSuspension::get(),
+                Error::<T>::SuspensionTooLong
+            );
 
-            // Unreserve the initial deposit for the recovery configuration.
-            T::Currency::unreserve(&who, match_to_bet.deposit);
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            let until = current_block_number + blocks;
+            <InPlayMarkets<T>>::try_mutate(match_id, |maybe_in_play| -> DispatchResult {
+                let in_play = maybe_in_play.as_mut().ok_or(Error::<T>::NotInPlay)?;
+                let betting_match =
+                    <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+                ensure!(
+                    current_block_number < betting_match.start + betting_match.length
+                        && !betting_match.has_result(),
+                    Error::<T>::MatchNotInPlay
+                );
+                in_play.suspended_until = Some(until);
+                Ok(())
+            })?;
 
-            // Return a successful DispatchResult
+            Self::deposit_event(Event::BettingSuspended(match_id, until));
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Returns a hash of match specs.
+        /// Resume the betting on a match played in-play before its suspension is over.
+        /// The dispatch origin for this call must be the `ResultOrigin`.
+        ///
+        /// Emit an event on success: `BettingResumed`.
         ///
         /// **Parameters:**
-        ///   * `betting_match` – Match specs.
-        pub fn get_match_hash(
-            betting_match: Match<T::BlockNumber, TeamName<T>, Bets<T>, BalanceOf<T>>,
-        ) -> T::Hash {
-            let entropy = (
-                betting_match.team1,
-                betting_match.team2,
-                betting_match.start,
-                betting_match.length,
-            )
-                .using_encoded(blake2_256);
-            Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
-                .expect("infinite length input; no invalid inputs for type; qed")
+        ///   * `origin` – Origin for the call. Must be the `ResultOrigin`.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `NotInPlay` – The match does not take bets while it is played.
+        #[pallet::weight(T::WeightInfo::resume_betting())]
+        pub fn resume_betting(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            T::ResultOrigin::ensure_origin(origin)?;
+
+            <InPlayMarkets<T>>::try_mutate(match_id, |maybe_in_play| -> DispatchResult {
+                let in_play = maybe_in_play.as_mut().ok_or(Error::<T>::NotInPlay)?;
+                in_play.suspended_until = None;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::BettingResumed(match_id));
+            Ok(())
+        }
+
+        /// Place a parlay on an outcome of several fixed-odds matches of the same bookmaker,
+        /// which only wins if every leg wins. Every leg is priced at the odds posted on its match
+        /// and the parlay at their product. A leg on a match that is voided or refunded is
+        /// dropped, and the parlay is settled as soon as a leg loses or every leg is settled.
+        /// The stake is reserved from the origin, and the winnings if every leg wins are reserved
+        /// from the bookmaker apart from the bonds of the matches, until the parlay is settled.
+        /// Emit an event on success: `ParlayPlaced`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `legs` – Id of the match and index of the outcome of every leg.
+        ///   * `stake` – Amount placed for the parlay.
+        ///
+        /// **Errors:**
+        ///   * `TooFewLegs` – There are less than two legs.
+        ///   * `TooManyLegs` – There are more than `MaxParlayLegs` legs.
+        ///   * `NoAvailableParlayId` – The parlay id counter has overflowed.
+        ///   * `DuplicateLeg` – Two legs are on the same match.
+        ///   * `MatchDoesNotExist` – The match of a leg doesn't exist.
+        ///   * `NotFixedOdds` – The match of a leg does not have fixed odds.
+        ///   * `UnknownOutcome` – The match of a leg has no outcome with that index.
+        ///   * `MatchHasStarted` – The match of a leg has started.
+        ///   * `MixedBookmakers` – The matches of the legs have different creators.
+        ///   * `TooManyParlays` – The match of a leg has `MaxParlaysPerMatch` parlays.
+        ///   * `InsufficientBond` – The bookmaker can not reserve the winnings of the parlay.
+        #[pallet::weight(T::WeightInfo::place_parlay(legs.len() as u32))]
+        pub fn place_parlay(
+            origin: OriginFor<T>,
+            legs: Vec<(MatchId, OutcomeIndex)>,
+            stake: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(legs.len() >= 2, Error::<T>::TooFewLegs);
+            ensure!(
+                legs.len() <= T::MaxParlayLegs::get() as usize,
+                Error::<T>::TooManyLegs
+            );
+
+            let parlay_id = <NextParlayId<T>>::get();
+            let next_parlay_id = parlay_id
+                .checked_add(1)
+                .ok_or(Error::<T>::NoAvailableParlayId)?;
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            let mut bookmaker = None;
+            let mut parlay_legs: Vec<Leg> = Vec::new();
+            for (match_id, outcome) in legs {
+                ensure!(
+                    !parlay_legs.iter().any(|leg| leg.match_id == match_id),
+                    Error::<T>::DuplicateLeg
+                );
+                let betting_match =
+                    <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+                let book = <Books<T>>::get(match_id).ok_or(Error::<T>::NotFixedOdds)?;
+                let odds = *book
+                    .odds
+                    .get(outcome as usize)
+                    .ok_or(Error::<T>::UnknownOutcome)?;
+                ensure!(
+                    current_block_number < betting_match.start,
+                    Error::<T>::MatchHasStarted
+                );
+                ensure!(
+                    *bookmaker.get_or_insert_with(|| betting_match.creator.clone())
+                        == betting_match.creator,
+                    Error::<T>::MixedBookmakers
+                );
+                <MatchParlays<T>>::try_append(match_id, parlay_id)
+                    .map_err(|_| Error::<T>::TooManyParlays)?;
+                parlay_legs.push(Leg {
+                    match_id,
+                    outcome,
+                    odds,
+                    state: LegState::Open,
+                });
+            }
+
+            let mut parlay = Parlay {
+                bettor: who.clone(),
+                bookmaker: bookmaker.ok_or(Error::<T>::TooFewLegs)?,
+                stake,
+                liability: Zero::zero(),
+                legs: parlay_legs
+                    .try_into()
+                    .map_err(|_| Error::<T>::TooManyLegs)?,
+            };
+            parlay.liability = parlay.winnings();
+
+            // The winnings are held until the parlay is settled, leaving the bonds of the legs
+            // to the bets on their matches
+            T::Currency::reserve(&who, stake)?;
+            T::Currency::reserve(&parlay.bookmaker, parlay.liability)
+                .map_err(|_| Error::<T>::InsufficientBond)?;
+            <Parlays<T>>::insert(parlay_id, &parlay);
+            <NextParlayId<T>>::put(next_parlay_id);
+
+            Self::deposit_event(Event::ParlayPlaced(parlay_id, who, stake, parlay.liability));
+            Ok(())
+        }
+
+        /// Settle a parlay whose legs all have a final result, before their matches are deleted.
+        /// The bettor is paid if every leg has won or been voided, and the bookmaker the stake
+        /// if a leg has lost.
+        /// Emit an event on success: `ParlayWon` or `ParlayLost`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `parlay_id` – Id of the parlay.
+        ///
+        /// **Errors:**
+        ///   * `ParlayDoesNotExist` – The parlay doesn't exist.
+        ///   * `ParlayNotSettled` – No leg has lost and some legs have no final result yet.
+        #[pallet::weight(T::WeightInfo::settle_parlay(T::MaxParlayLegs::get()))]
+        pub fn settle_parlay(origin: OriginFor<T>, parlay_id: ParlayId) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let mut parlay = <Parlays<T>>::get(parlay_id).ok_or(Error::<T>::ParlayDoesNotExist)?;
+            let open: Vec<_> = parlay
+                .legs
+                .iter()
+                .filter(|leg| leg.state == LegState::Open)
+                .map(|leg| leg.match_id)
+                .collect();
+            let mut lost = false;
+            for match_id in open {
+                let settlement = <Matches<T>>::get(match_id).and_then(|betting_match| {
+                    Self::ensure_result_final(match_id, &betting_match).ok()
+                });
+                if let Some(settlement) = settlement {
+                    lost |= !Self::settle_leg(&mut parlay, match_id, settlement);
+                }
+            }
+            ensure!(
+                lost || parlay.legs.iter().all(|leg| leg.state != LegState::Open),
+                Error::<T>::ParlayNotSettled
+            );
+            Self::conclude_parlay(parlay_id, parlay, lost)
+        }
+
+        /// Create a tournament between a list of registered teams and the outright market on its
+        /// winner, whose outcomes are named after the teams. A league schedules a match between every two teams, one
+        /// round after another, and a knockout the matches of its first round, pairing the teams
+        /// in order.
+        /// Emit an event on success: `TournamentCreated`, and `MatchCreated` for every match.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `format` – Whether the tournament is a league or a knockout.
+        ///   * `teams` – Ids of the teams.
+        ///   * `start` – Time when the first round starts (in blocks).
+        ///   * `round_length` – Time between the start of two rounds (in blocks).
+        ///   * `match_length` – Duration of every match (in blocks).
+        ///   * `commission` – Part of the pot of every match taken by the organizer.
+        ///
+        /// **Errors:**
+        ///   * `RoundTooShort` – The matches are longer than the rounds.
+        ///   * `BracketNotPowerOfTwo` – The number of teams of a knockout is not a power of two.
+        ///   * `TooManyMatches` – There are more than `MaxTournamentMatches` matches in a round.
+        ///   * `NoAvailableTournamentId` – The tournament id counter has overflowed.
+        ///   * `TooFewOutcomes` – There are less than two teams.
+        ///   * `TooManyOutcomes` – There are more than `MaxOutcomes` teams.
+        ///   * `DuplicateTeam` – A team is listed twice.
+        ///   * `UnknownTeam` – A team is not registered.
+        ///   * `TimeMatchOver` – The tournament is created when it is over.
+        ///   * `CommissionTooHigh` – The commission is higher than `MaxCreatorCommission`.
+        ///   * `MatchAlreadyExists` – A market with the same teams and times as the outright market
+        ///     already exists.
+        #[pallet::weight(T::WeightInfo::create_tournament(
+            format.scheduled_matches(teams.len() as u32)
+        ))]
+        pub fn create_tournament(
+            origin: OriginFor<T>,
+            format: TournamentFormat,
+            teams: Vec<TeamId>,
+            start: T::BlockNumber,
+            round_length: T::BlockNumber,
+            match_length: T::BlockNumber,
+            commission: Permill,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(match_length <= round_length, Error::<T>::RoundTooShort);
+            ensure!(teams.len() >= 2, Error::<T>::TooFewOutcomes);
+            ensure!(
+                teams.len() <= T::MaxOutcomes::get() as usize,
+                Error::<T>::TooManyOutcomes
+            );
+            ensure!(
+                teams
+                    .iter()
+                    .enumerate()
+                    .all(|(i, team_id)| !teams[..i].contains(team_id)),
+                Error::<T>::DuplicateTeam
+            );
+
+            let team_count = teams.len() as u32;
+            let (rounds, fixtures) = match format {
+                TournamentFormat::League => (
+                    team_count + team_count % 2 - 1,
+                    Self::round_robin(team_count),
+                ),
+                TournamentFormat::Knockout => {
+                    ensure!(
+                        team_count.is_power_of_two(),
+                        Error::<T>::BracketNotPowerOfTwo
+                    );
+                    let fixtures = (0..team_count / 2)
+                        .map(|i| (0, 2 * i, 2 * i + 1))
+                        .collect::<Vec<_>>();
+                    (1, fixtures)
+                }
+            };
+            ensure!(
+                fixtures.len() <= T::MaxTournamentMatches::get() as usize,
+                Error::<T>::TooManyMatches
+            );
+
+            let tournament_id = <NextTournamentId<T>>::get();
+            let next_tournament_id = tournament_id
+                .checked_add(1)
+                .ok_or(Error::<T>::NoAvailableTournamentId)?;
+
+            // The outright market lasts until the end of the last round
+            let total_rounds = match format {
+                TournamentFormat::League => rounds,
+                TournamentFormat::Knockout => team_count.trailing_zeros(),
+            };
+            let (outright, names) = Self::new_match(
+                who.clone(),
+                Self::team_names(&teams)?,
+                start,
+                round_length.saturating_mul(total_rounds.into()),
+                commission,
+                None,
+            )?;
+            let outright_id = Self::insert_match(outright, names, true, None)?;
+
+            let mut tournament = Tournament {
+                organizer: who.clone(),
+                format,
+                teams: teams.try_into().map_err(|_| Error::<T>::TooManyOutcomes)?,
+                start,
+                round_length,
+                match_length,
+                commission,
+                rounds,
+                fixtures: Default::default(),
+                outright: outright_id,
+            };
+            tournament.fixtures = fixtures
+                .into_iter()
+                .map(|(round, home, away)| {
+                    Self::schedule_fixture(tournament_id, &tournament, round, home, away)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .try_into()
+                .map_err(|_| Error::<T>::TooManyMatches)?;
+
+            <Tournaments<T>>::insert(tournament_id, tournament);
+            <NextTournamentId<T>>::put(next_tournament_id);
+
+            Self::deposit_event(Event::TournamentCreated(tournament_id, who, outright_id));
+            Ok(())
+        }
+
+        /// Advance a tournament once every match of its current round has a final result.
+        /// A knockout replays its void matches within the same round, or schedules the next
+        /// round between the winners, and a league ranks the teams by points. The outright market
+        /// is settled on the winner of the final or the leader of the league, and voided if the
+        /// league ends in a tie.
+        /// Tournaments are advanced by `on_idle` once every match of the round is deleted, and
+        /// anyone can advance a tournament before that.
+        ///
+        /// Emit an event on success: `RoundScheduled`, or `TournamentFinished` and `MatchResult`
+        /// or `MatchVoided` for the outright market.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `tournament_id` – Id of the tournament.
+        ///
+        /// **Errors:**
+        ///   * `TournamentDoesNotExist` – The tournament doesn't exist.
+        ///   * `RoundNotOver` – Some matches of the round have no final result yet.
+        #[pallet::weight(T::WeightInfo::advance_tournament(T::MaxTournamentMatches::get()))]
+        pub fn advance_tournament(
+            origin: OriginFor<T>,
+            tournament_id: TournamentId,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::do_advance_tournament(tournament_id)
+        }
+
+        /// Set the result of an existing match.
+        /// The dispatch origin for this call must be the `ResultOrigin`.
+        /// The result can be disputed during the `DisputePeriod`.
+        ///
+        /// Emit an event on success: `MatchResult`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be the `ResultOrigin`.
+        ///   * `match_id` – Id of the match.
+        ///   * `result` – Index of the outcome of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `TimeMatchNotOver` – If the match is not over, set the result is not allowed.
+        ///   * `ResultAlreadySet` – The result of the match has already been set.
+        ///   * `UnknownOutcome` – The match has no outcome with that index.
+        #[pallet::weight(T::WeightInfo::set_result())]
+        pub fn set_result(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            match_result: OutcomeIndex,
+        ) -> DispatchResult {
+            // Only the result origin can call this extrinsic.
+            T::ResultOrigin::ensure_origin(origin)?;
+
+            //Find the match where user wants to place the bet
+            let mut match_to_set_result =
+                <Matches<T>>::take(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(
+                match_to_set_result.has_outcome(match_result),
+                Error::<T>::UnknownOutcome
+            );
+
+            // Check if start and length are valid
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number > (match_to_set_result.start + match_to_set_result.length),
+                Error::<T>::TimeMatchNotOver
+            );
+            ensure!(
+                !match_to_set_result.has_result(),
+                Error::<T>::ResultAlreadySet
+            );
+
+            match_to_set_result.result = Some(match_result);
+            let dispute_end = current_block_number + T::DisputePeriod::get();
+            match_to_set_result.dispute_end = Some(dispute_end);
+            Self::enqueue(dispute_end, match_id);
+
+            // Store the updated match result
+            <Matches<T>>::insert(match_id, match_to_set_result);
+
+            // Emit an event.
+            Self::deposit_event(Event::MatchResult(match_id, match_result));
+
+            // Return a successful DispatchResult
+            Ok(())
+        }
+
+        /// Notify the score of a two-team match. The match and every market derived from it
+        /// that still has no result are settled from the score.
+        /// The dispatch origin for this call must be the `ResultOrigin`.
+        ///
+        /// Emit an event on success: `ScoreSet` for the match and every derived market.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be the `ResultOrigin`.
+        ///   * `match_id` – Id of the match.
+        ///   * `score` – Goals scored by every team.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NotScoreMarket` – The match is not settled from its score.
+        ///   * `TimeMatchNotOver` – If the match is not over, set the score is not allowed.
+        ///   * `ResultAlreadySet` – The result of the match has already been set.
+        #[pallet::weight(T::WeightInfo::set_score(T::MaxDerivedMarkets::get()))]
+        pub fn set_score(origin: OriginFor<T>, match_id: MatchId, score: Score) -> DispatchResult {
+            // Only the result origin can call this extrinsic.
+            T::ResultOrigin::ensure_origin(origin)?;
+
+            Self::do_set_score(match_id, score)?;
+            for market_id in Self::unresolved_markets(match_id) {
+                Self::do_set_score(market_id, score)?;
+            }
+
+            Ok(())
+        }
+
+        /// Void a match that has been abandoned, so that every bet is refunded at face value
+        /// once the void can no longer be disputed. The markets derived from it that have no
+        /// result yet are voided too.
+        /// The dispatch origin for this call must be the `ResultOrigin`.
+        ///
+        /// Emit an event on success: `MatchVoided` for the match and every derived market.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be the `ResultOrigin`.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `TimeMatchNotOver` – If the match is not over, voiding it is not allowed.
+        ///   * `ResultAlreadySet` – The result of the match has already been set.
+        #[pallet::weight(T::WeightInfo::void_match(T::MaxDerivedMarkets::get()))]
+        pub fn void_match(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            // Only the result origin can call this extrinsic.
+            T::ResultOrigin::ensure_origin(origin)?;
+
+            Self::do_void_match(match_id)?;
+            for market_id in Self::unresolved_markets(match_id) {
+                Self::do_void_match(market_id)?;
+            }
+
+            Ok(())
+        }
+
+        /// When a match ends the owner of the match can distribute funds to the winners that have
+        /// not claimed yet and delete the match.
+        /// If nobody bet on the result the pot is settled following the `NoWinnerPolicy`.
+        ///
+        /// Emit an event on success: `MatchClosed`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed by the creator of the match.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NotMatchCreator` – The origin is not the creator of the match.
+        ///   * `MatchNotResult` – The match still has not a result.
+        ///   * `DisputePeriodNotOver` – The result can still be disputed.
+        ///   * `MatchDisputed` – The result is being disputed.
+        #[pallet::weight(T::WeightInfo::distribute_winnings(T::MaxBetsPerMatch::get()))]
+        pub fn distribute_winnings(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            // Get the match that user wants to close
+            let match_to_bet = <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+
+            ensure!(match_to_bet.creator == who, Error::<T>::NotMatchCreator);
+            Self::do_distribute_winnings(match_id, match_to_bet)
+        }
+
+        /// Claim the winnings of the bets placed by the origin in a match.
+        /// On a half win, the bettors on every outcome also claim the refunded half of their bets.
+        /// The shares bought from a market maker are redeemed, one unit for every winning share.
+        /// The match is deleted once every winner has claimed.
+        /// If nobody bet on the result the pot is settled following the `NoWinnerPolicy`.
+        ///
+        /// Emit an event on success: `WinningsClaimed`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `MatchNotResult` – The match still has not a result.
+        ///   * `DisputePeriodNotOver` – The result can still be disputed.
+        ///   * `MatchDisputed` – The result is being disputed.
+        ///   * `NothingToClaim` – The origin has no winnings left to claim in that match.
+        #[pallet::weight(T::WeightInfo::claim_winnings(T::MaxBetsPerMatch::get()))]
+        pub fn claim_winnings(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            let mut match_to_claim =
+                <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            let settlement = Self::ensure_result_final(match_id, &match_to_claim)?;
+            if let Some(maker) = <MarketMakers<T>>::get(match_id) {
+                Self::settle_shares(match_id, &mut match_to_claim, &maker)?;
+                let pot = Self::pot_account(match_id);
+                return Self::claim_positions(
+                    match_id,
+                    match_to_claim,
+                    settlement,
+                    who,
+                    |betting_match, bettor, outcome, shares| {
+                        let amount =
+                            Self::redemption(betting_match, &maker, settlement, outcome, shares)?;
+                        T::Currency::transfer(&pot, bettor, amount, AllowDeath)?;
+                        Ok(amount)
+                    },
+                );
+            }
+            if settlement == Settlement::Push {
+                return Self::do_distribute_winnings(match_id, match_to_claim);
+            }
+            if let Some(book) = <Books<T>>::get(match_id) {
+                return Self::claim_positions(
+                    match_id,
+                    match_to_claim,
+                    settlement,
+                    who,
+                    |betting_match, bettor, result, amount| {
+                        Self::pay_at_odds(
+                            match_id,
+                            betting_match,
+                            &book,
+                            settlement,
+                            bettor,
+                            result,
+                            amount,
+                        )
+                    },
+                );
+            }
+
+            let (total_bet, total_winners) =
+                Self::bet_totals(match_id, &match_to_claim, settlement);
+            if total_winners.is_zero() {
+                return Self::do_distribute_winnings(match_id, match_to_claim);
+            }
+            let winners_pot = Self::settle(match_id, &mut match_to_claim, total_bet)?;
+
+            let positions: Vec<_> = <Bets<T>>::iter_prefix((match_id, who.clone()))
+                .filter(|(result, bet)| settlement.pays(*result) && !bet.claimed)
+                .collect();
+            ensure!(!positions.is_empty(), Error::<T>::NothingToClaim);
+
+            let mut amount_paid: BalanceOf<T> = Zero::zero();
+            for (result, mut bet) in positions {
+                let (refund, amount_won) = Self::payout(
+                    settlement,
+                    result,
+                    bet.amount,
+                    Self::weighted_amount(match_id, &who, result, bet.amount),
+                    total_winners,
+                    winners_pot,
+                );
+                bet.claimed = true;
+                match_to_claim.claimed_stakes += bet.amount;
+                match_to_claim.paid_winnings += amount_won;
+                amount_paid += refund + amount_won;
+                <Bets<T>>::insert((match_id, &who, result), bet);
+            }
+
+            let pot = Self::pot_account(match_id);
+            Self::transfer_asset(match_to_claim.asset, &pot, &who, amount_paid)?;
+
+            // Emit an event.
+            Self::deposit_event(Event::WinningsClaimed(match_id, who, amount_paid));
+
+            // Delete the match once every winner has claimed
+            if match_to_claim.claimed_stakes == Self::paid_stakes(&match_to_claim, settlement) {
+                // The remainder left by the rounding goes to the creator
+                let dust = winners_pot.saturating_sub(match_to_claim.paid_winnings);
+                Self::transfer_asset(match_to_claim.asset, &pot, &match_to_claim.creator, dust)?;
+                Self::close_match(match_id, match_to_claim)?;
+            } else {
+                <Matches<T>>::insert(match_id, match_to_claim);
+            }
+
+            // Return a successful DispatchResult
+            Ok(())
+        }
+
+        /// Delete a match whose `ClaimPeriod` is over.
+        /// The winnings that have not been claimed and the rounding remainder go to the creator
+        /// of the match.
+        /// If nobody bet on the result the pot is settled following the `NoWinnerPolicy`.
+        ///
+        /// Emit an event on success: `MatchClosed`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `MatchNotResult` – The match still has not a result.
+        ///   * `DisputePeriodNotOver` – The result can still be disputed.
+        ///   * `MatchDisputed` – The result is being disputed.
+        ///   * `ClaimPeriodNotOver` – The winners can still claim their winnings.
+        #[pallet::weight(T::WeightInfo::reap_match(T::MaxBetsPerMatch::get()))]
+        pub fn reap_match(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            // Check that the extrinsic was signed.
+            ensure_signed(origin)?;
+
+            let mut match_to_reap =
+                <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            let settlement = Self::ensure_result_final(match_id, &match_to_reap)?;
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number
+                    >= match_to_reap.dispute_end.unwrap_or_default() + T::ClaimPeriod::get(),
+                Error::<T>::ClaimPeriodNotOver
+            );
+
+            // The unclaimed shares and bets of a match with a market maker or fixed odds are left
+            // in the pot for the creator
+            if <MarketMakers<T>>::contains_key(match_id) {
+                return Self::close_match(match_id, match_to_reap);
+            }
+            if settlement == Settlement::Push {
+                return Self::do_distribute_winnings(match_id, match_to_reap);
+            }
+            if <Books<T>>::contains_key(match_id) {
+                return Self::close_match(match_id, match_to_reap);
+            }
+            let (total_bet, total_winners) = Self::bet_totals(match_id, &match_to_reap, settlement);
+            if total_winners.is_zero() {
+                return Self::do_distribute_winnings(match_id, match_to_reap);
+            }
+            let winners_pot = Self::settle(match_id, &mut match_to_reap, total_bet)?;
+
+            // The unclaimed winnings and the rounding remainder go to the creator
+            let unclaimed = winners_pot.saturating_sub(match_to_reap.paid_winnings);
+            Self::transfer_asset(
+                match_to_reap.asset,
+                &Self::pot_account(match_id),
+                &match_to_reap.creator,
+                unclaimed,
+            )?;
+
+            Self::close_match(match_id, match_to_reap)
+        }
+
+        /// Cancel a match, refunding every bet and the deposit of the creator.
+        /// The creator can cancel the match before it starts, the `CancelOrigin` at any time
+        /// before its winnings are claimed.
+        ///
+        /// Emit an event on success: `MatchCancelled`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed by the creator or be the `CancelOrigin`.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NotMatchCreator` – The origin is not the creator of the match.
+        ///   * `MatchHasStarted` – The creator can not cancel a match that has started.
+        ///   * `WinningsAlreadyClaimed` – Some bets of the match have already been paid.
+        #[pallet::weight(T::WeightInfo::cancel_match(T::MaxBetsPerMatch::get()))]
+        pub fn cancel_match(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            // The privileged origin can cancel any match, otherwise it has to be signed.
+            let maybe_who = match T::CancelOrigin::try_origin(origin) {
+                Ok(_) => None,
+                Err(origin) => Some(ensure_signed(origin)?),
+            };
+
+            // Get the match to cancel, deleting it
+            let match_to_cancel =
+                <Matches<T>>::take(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+
+            if let Some(who) = maybe_who {
+                ensure!(match_to_cancel.creator == who, Error::<T>::NotMatchCreator);
+                let current_block_number = <frame_system::Pallet<T>>::block_number();
+                ensure!(
+                    current_block_number < match_to_cancel.start,
+                    Error::<T>::MatchHasStarted
+                );
+            }
+            // The refunds would come out of the stakes of the losers already paid to the winners
+            ensure!(
+                match_to_cancel.settled_pot.is_none()
+                    && !<Bets<T>>::iter_prefix_values((match_id,)).any(|bet| bet.claimed),
+                Error::<T>::WinningsAlreadyClaimed
+            );
+
+            Self::do_refund_match(match_id, match_to_cancel)?;
+
+            // Emit an event.
+            Self::deposit_event(Event::MatchCancelled(match_id));
+
+            // Return a successful DispatchResult
+            Ok(())
+        }
+
+        /// Refund a match whose result has not been set `ResultTimeout` blocks after its end.
+        /// The origin is paid the `TimeoutIncentive` out of the deposit of the creator.
+        ///
+        /// Emit an event on success: `MatchTimedOut`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `ResultAlreadySet` – The result of the match has been set.
+        ///   * `ResultTimeoutNotOver` – The result of the match can still be set.
+        #[pallet::weight(T::WeightInfo::refund_match(T::MaxBetsPerMatch::get()))]
+        pub fn refund_match(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut match_to_refund =
+                <Matches<T>>::take(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(!match_to_refund.has_result(), Error::<T>::ResultAlreadySet);
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number >= Self::result_deadline(&match_to_refund),
+                Error::<T>::ResultTimeoutNotOver
+            );
+
+            // Pay the incentive out of the deposit of the creator
+            let incentive = T::TimeoutIncentive::get().min(match_to_refund.deposit);
+            T::Currency::repatriate_reserved(
+                &match_to_refund.creator,
+                &who,
+                incentive,
+                BalanceStatus::Free,
+            )?;
+            match_to_refund.deposit -= incentive;
+
+            Self::do_refund_match(match_id, match_to_refund)?;
+
+            Self::deposit_event(Event::MatchTimedOut(match_id, who, incentive));
+            Ok(())
+        }
+
+        /// Opt in to settle a match automatically `grace` blocks after its end, so nobody has to
+        /// call `distribute_winnings`. If the result is not final by then, the settlement is
+        /// retried every `SettlementRetryPeriod` blocks, and the match is refunded once the
+        /// `ResultTimeout` is over.
+        ///
+        /// Emit an event on success: `SettlementScheduled`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed by the creator of the match.
+        ///   * `match_id` – Id of the match.
+        ///   * `grace` – Number of blocks after the end of the match to wait for its result.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NotMatchCreator` – The origin is not the creator of the match.
+        ///   * `SettlementAlreadyScheduled` – The settlement of the match is already scheduled.
+        #[pallet::weight(T::WeightInfo::schedule_settlement())]
+        pub fn schedule_settlement(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            grace: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let betting_match = <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(betting_match.creator == who, Error::<T>::NotMatchCreator);
+            ensure!(
+                !<ScheduledSettlements<T>>::contains_key(match_id),
+                Error::<T>::SettlementAlreadyScheduled
+            );
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            let when = (betting_match.start + betting_match.length + grace)
+                .max(current_block_number + One::one());
+            Self::schedule_settlement_at(match_id, when)
+        }
+
+        /// Settle a match on behalf of the scheduler, or schedule a new attempt if it can not be
+        /// settled yet.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be root.
+        ///   * `match_id` – Id of the match.
+        #[pallet::weight(T::WeightInfo::settle_scheduled(T::MaxBetsPerMatch::get()))]
+        pub fn settle_scheduled(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            ensure_root(origin)?;
+
+            // The match may have been settled by other means in the meantime
+            if !<Matches<T>>::contains_key(match_id) {
+                return Ok(());
+            }
+
+            if with_storage_layer(|| Self::finalise_match(match_id)).is_err() {
+                let current_block_number = <frame_system::Pallet<T>>::block_number();
+                Self::schedule_settlement_at(
+                    match_id,
+                    current_block_number + T::SettlementRetryPeriod::get(),
+                )?;
+            }
+            Ok(())
+        }
+
+        /// Dispute the result of a match, reserving the `DisputeBond`.
+        /// The result can only be disputed during the `DisputePeriod` after it was set.
+        ///
+        /// Emit an event on success: `ResultDisputed`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be signed.
+        ///   * `match_id` – Id of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `MatchNotResult` – The match still has not a result.
+        ///   * `DisputePeriodOver` – The result can no longer be disputed.
+        ///   * `AlreadyDisputed` – You already disputed the result of that match.
+        ///   * `MaxDisputes` – The result has reach its dispute limit.
+        #[pallet::weight(T::WeightInfo::dispute_result())]
+        pub fn dispute_result(origin: OriginFor<T>, match_id: MatchId) -> DispatchResult {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            let match_to_dispute =
+                <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            let dispute_end = match_to_dispute
+                .dispute_end
+                .ok_or(Error::<T>::MatchNotResult)?;
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number < dispute_end,
+                Error::<T>::DisputePeriodOver
+            );
+
+            let mut disputes = <Disputes<T>>::get(match_id);
+            ensure!(
+                !disputes.iter().any(|(disputer, _)| disputer == &who),
+                Error::<T>::AlreadyDisputed
+            );
+            let bond = T::DisputeBond::get();
+            disputes
+                .try_push((who.clone(), bond))
+                .map_err(|_| Error::<T>::MaxDisputes)?;
+
+            // Reserve the bond
+            T::Currency::reserve(&who, bond)?;
+
+            <Disputes<T>>::insert(match_id, disputes);
+
+            // Emit an event.
+            Self::deposit_event(Event::ResultDisputed(match_id, who));
+
+            // Return a successful DispatchResult
+            Ok(())
+        }
+
+        /// Resolve the disputes of a match result.
+        /// The dispatch origin for this call must be the `DisputeResolutionOrigin`.
+        ///
+        /// If the given result is the one already set, the result is upheld and the bonds are slashed.
+        /// Otherwise the result is overturned and the bonds are returned to the disputers. A void
+        /// or a result settled from a score is replaced by the given result.
+        /// In both cases the result can no longer be disputed.
+        ///
+        /// Emit an event on success: `ResultUpheld` or `ResultOverturned`.
+        ///
+        /// **Parameters:**
+        ///   * `origin` – Origin for the call. Must be the `DisputeResolutionOrigin`.
+        ///   * `match_id` – Id of the match.
+        ///   * `match_result` – The right result of the match.
+        ///
+        /// **Errors:**
+        ///   * `MatchDoesNotExist` – A match selected for the bet doesn't exist.
+        ///   * `NoDispute` – The result of the match is not being disputed.
+        #[pallet::weight(T::WeightInfo::resolve_dispute())]
+        pub fn resolve_dispute(
+            origin: OriginFor<T>,
+            match_id: MatchId,
+            match_result: OutcomeIndex,
+        ) -> DispatchResult {
+            // Only the dispute resolution origin can call this extrinsic.
+            T::DisputeResolutionOrigin::ensure_origin(origin)?;
+
+            let mut match_to_resolve =
+                <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(
+                match_to_resolve.has_outcome(match_result),
+                Error::<T>::UnknownOutcome
+            );
+
+            let disputes = <Disputes<T>>::take(match_id);
+            ensure!(!disputes.is_empty(), Error::<T>::NoDispute);
+
+            if match_to_resolve.result == Some(match_result) {
+                // The result was right, the disputers lose their bonds.
+                for (disputer, bond) in disputes {
+                    let (imbalance, _) = T::Currency::slash_reserved(&disputer, bond);
+                    T::Slashed::on_unbalanced(imbalance);
+                }
+                Self::deposit_event(Event::ResultUpheld(match_id));
+            } else {
+                for (disputer, bond) in disputes {
+                    T::Currency::unreserve(&disputer, bond);
+                }
+                // The overturned result replaces the one settled from the score or the void
+                match_to_resolve.result = Some(match_result);
+                match_to_resolve.score = None;
+                match_to_resolve.voided = false;
+                Self::deposit_event(Event::ResultOverturned(match_id, match_result));
+            }
+
+            // Close the dispute period
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            match_to_resolve.dispute_end = Some(current_block_number);
+            Self::enqueue(current_block_number, match_id);
+            <Matches<T>>::insert(match_id, match_to_resolve);
+
+            // Return a successful DispatchResult
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Checks the name and the metadata of a team.
+        fn new_team(name: Vec<u8>, metadata: Option<Vec<u8>>) -> Result<TeamOf<T>, DispatchError> {
+            let name: TeamName<T> = name.try_into().map_err(|_| Error::<T>::TeamNameTooLong)?;
+            let metadata = metadata
+                .map(|metadata| {
+                    metadata
+                        .try_into()
+                        .map_err(|_| Error::<T>::TeamMetadataTooLong)
+                })
+                .transpose()?;
+            Ok(Team { name, metadata })
+        }
+
+        /// Returns the key of a team name in `TeamIds`: trimmed, with every run of whitespace
+        /// collapsed to a single space, and lowercased.
+        pub(crate) fn team_key(name: &[u8]) -> Result<TeamName<T>, DispatchError> {
+            let key = name
+                .split(|byte| byte.is_ascii_whitespace())
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(&b' ')
+                .to_ascii_lowercase();
+            ensure!(!key.is_empty(), Error::<T>::EmptyTeamName);
+            key.try_into()
+                .map_err(|_| Error::<T>::TeamNameTooLong.into())
+        }
+
+        /// Stores a new team and returns its id.
+        pub(crate) fn insert_team(team: TeamOf<T>) -> Result<TeamId, DispatchError> {
+            let key = Self::team_key(&team.name)?;
+            ensure!(
+                !<TeamIds<T>>::contains_key(&key),
+                Error::<T>::TeamAlreadyRegistered
+            );
+
+            let team_id = <NextTeamId<T>>::get();
+            let next_team_id = team_id
+                .checked_add(1)
+                .ok_or(Error::<T>::NoAvailableTeamId)?;
+
+            <TeamIds<T>>::insert(key, team_id);
+            <Teams<T>>::insert(team_id, team);
+            <NextTeamId<T>>::put(next_team_id);
+            Ok(team_id)
+        }
+
+        /// Returns the names of registered teams.
+        fn team_names(teams: &[TeamId]) -> Result<Vec<Vec<u8>>, DispatchError> {
+            teams
+                .iter()
+                .map(|team_id| {
+                    <Teams<T>>::get(team_id)
+                        .map(|team| team.name.to_vec())
+                        .ok_or_else(|| Error::<T>::UnknownTeam.into())
+                })
+                .collect()
+        }
+
+        /// Checks the specs of a new match with a list of named outcomes and returns it with the
+        /// names, before it is stored by `insert_match`.
+        fn new_match(
+            who: T::AccountId,
+            names: Vec<Vec<u8>>,
+            start: T::BlockNumber,
+            length: T::BlockNumber,
+            commission: Permill,
+            asset: Option<AssetIdOf<T>>,
+        ) -> Result<(MatchOf<T>, OutcomeNames<T>), DispatchError> {
+            ensure!(
+                commission <= T::MaxCreatorCommission::get(),
+                Error::<T>::CommissionTooHigh
+            );
+            if let Some(asset) = asset {
+                ensure!(T::Assets::asset_exists(asset), Error::<T>::UnknownAsset);
+            }
+
+            // Check if start and length are valid
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number < (start + length),
+                Error::<T>::TimeMatchOver
+            );
+
+            ensure!(names.len() >= 2, Error::<T>::TooFewOutcomes);
+            let names: OutcomeNames<T> = names
+                .into_iter()
+                .map(|name| name.try_into().map_err(|_| Error::<T>::TeamNameTooLong))
+                .collect::<Result<Vec<_>, _>>()?
+                .try_into()
+                .map_err(|_| Error::<T>::TooManyOutcomes)?;
+            let outcomes = names
+                .iter()
+                .map(|name| Outcome {
+                    name: name.clone(),
+                    total: Zero::zero(),
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| Error::<T>::TooManyOutcomes)?;
+
+            // Create the betting match
+            let betting_match = Match {
+                creator: who,
+                start,
+                length,
+                teams: None,
+                outcomes,
+                market: None,
+                parent: None,
+                score: None,
+                voided: false,
+                result: None,
+                dispute_end: None,
+                asset,
+                bet_count: 0,
+                rollover: Zero::zero(),
+                commission,
+                settled_pot: None,
+                claimed_stakes: Zero::zero(),
+                paid_winnings: Zero::zero(),
+                deposit: T::MatchDeposit::get(),
+            };
+            Ok((betting_match, names))
+        }
+
+        /// Stores a new match and returns its id. Only a parimutuel `pool` takes the pot rolled
+        /// over from a previous match of the creator. The fixture of a `tournament` is linked to
+        /// it.
+        fn insert_match(
+            mut betting_match: MatchOf<T>,
+            names: OutcomeNames<T>,
+            pool: bool,
+            tournament: Option<TournamentId>,
+        ) -> Result<MatchId, DispatchError> {
+            let who = betting_match.creator.clone();
+            let (start, length, asset) = (
+                betting_match.start,
+                betting_match.length,
+                betting_match.asset,
+            );
+            let match_id = <NextMatchId<T>>::get();
+            let next_match_id = match_id
+                .checked_add(1)
+                .ok_or(Error::<T>::NoAvailableMatchId)?;
+
+            // Check if match already exists by checking its specs hash. The fixtures of a
+            // tournament are told apart by their tournament, so they do not take a hash.
+            let match_hash = tournament
+                .is_none()
+                .then(|| Self::get_match_hash(betting_match.clone()));
+            if let Some(match_hash) = &match_hash {
+                ensure!(
+                    !<MatchHashes<T>>::contains_key(match_hash),
+                    Error::<T>::MatchAlreadyExists
+                );
+            }
+
+            // Reserve the deposit
+            T::Currency::reserve(&who, T::MatchDeposit::get())?;
+
+            // Seed the pot with the existential deposit, so it can hold bets of any amount
+            let pot = Self::pot_account(match_id);
+            T::Currency::transfer(&who, &pot, T::Currency::minimum_balance(), AllowDeath)?;
+            if let Some(asset) = asset {
+                Self::transfer_asset(Some(asset), &who, &pot, T::Assets::minimum_balance(asset))?;
+            }
+
+            // Add the pot rolled over from a previous match
+            if pool {
+                if let Some((holder_id, rollover)) = <Rollovers<T>>::take(&who, asset) {
+                    Self::transfer_asset(asset, &Self::pot_account(holder_id), &pot, rollover)?;
+                    Self::sweep_pot(holder_id, asset, &who)?;
+                    betting_match.rollover = rollover;
+                }
+            }
+
+            // Store the match hash with its id, or the tournament of a fixture.
+            if let Some(match_hash) = match_hash {
+                <MatchHashes<T>>::insert(match_hash, match_id);
+            }
+            if let Some(tournament_id) = tournament {
+                <TournamentMatches<T>>::insert(match_id, tournament_id);
+            }
+
+            // Store the betting match in the list of open matches
+            <MatchesByCreator<T>>::insert(&who, match_id, ());
+            <NextMatchId<T>>::put(next_match_id);
+
+            // Refund the match if it never gets a result
+            Self::enqueue(Self::result_deadline(&betting_match), match_id);
+            <Matches<T>>::insert(match_id, betting_match);
+
+            // Emit an event.
+            Self::deposit_event(Event::MatchCreated(match_id, who, names, start, length));
+
+            Ok(match_id)
+        }
+
+        /// Sets the score of a match and the result it settles.
+        fn do_set_score(match_id: MatchId, score: Score) -> DispatchResult {
+            let mut betting_match =
+                <Matches<T>>::take(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(betting_match.market.is_some(), Error::<T>::NotScoreMarket);
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number > (betting_match.start + betting_match.length),
+                Error::<T>::TimeMatchNotOver
+            );
+            ensure!(!betting_match.has_result(), Error::<T>::ResultAlreadySet);
+
+            betting_match.score = Some(score);
+            betting_match.result = betting_match.settlement().and_then(Settlement::winner);
+            let dispute_end = current_block_number + T::DisputePeriod::get();
+            betting_match.dispute_end = Some(dispute_end);
+            Self::enqueue(dispute_end, match_id);
+            <Matches<T>>::insert(match_id, betting_match);
+
+            Self::deposit_event(Event::ScoreSet(match_id, score));
+            Ok(())
+        }
+
+        /// Returns the positions of a bettor on every result of a match.
+        pub fn positions(
+            match_id: MatchId,
+            who: &T::AccountId,
+        ) -> Vec<(OutcomeIndex, BalanceOf<T>)> {
+            <Bets<T>>::iter_prefix((match_id, who.clone()))
+                .map(|(result, bet)| (result, bet.amount))
+                .collect()
+        }
+
+        /// Takes an amount off the bet of a bettor, the whole bet if `None`, and refunds it
+        /// less the `CancellationFee`, which goes to the creator.
+        fn do_reduce_bet(
+            match_id: MatchId,
+            who: T::AccountId,
+            result: OutcomeIndex,
+            amount: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            let mut betting_match =
+                <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            ensure!(
+                !<MarketMakers<T>>::contains_key(match_id),
+                Error::<T>::MarketMakerMatch
+            );
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number < betting_match.start,
+                Error::<T>::MatchHasStarted
+            );
+
+            let amount =
+                Self::take_from_position(match_id, &mut betting_match, &who, result, amount)?;
+
+            // Refund the bet and pay the cancellation fee to the creator
+            let pot = Self::pot_account(match_id);
+            let fee = T::CancellationFee::get() * amount;
+            Self::transfer_asset(betting_match.asset, &pot, &who, amount - fee)?;
+            Self::transfer_asset(betting_match.asset, &pot, &betting_match.creator, fee)?;
+            <Matches<T>>::insert(match_id, betting_match);
+
+            Self::deposit_event(Event::BetCancelled(match_id, who, amount, result, fee));
+            Ok(())
+        }
+
+        /// Adds an amount to the position of a bettor on an outcome and to the totals of the
+        /// match.
+        fn add_to_position(
+            match_id: MatchId,
+            betting_match: &mut MatchOf<T>,
+            who: &T::AccountId,
+            result: OutcomeIndex,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            <Bets<T>>::try_mutate((match_id, who, result), |maybe_bet| -> DispatchResult {
+                match maybe_bet {
+                    Some(bet) => bet.amount += amount,
+                    None => {
+                        ensure!(
+                            betting_match.bet_count < T::MaxBetsPerMatch::get(),
+                            Error::<T>::MaxBets
+                        );
+                        betting_match.bet_count += 1;
+                        *maybe_bet = Some(Bet {
+                            amount,
+                            claimed: false,
+                        });
+                    }
+                }
+                Ok(())
+            })?;
+            *betting_match
+                .total_on_mut(result)
+                .ok_or(Error::<T>::UnknownOutcome)? += amount;
+            Ok(())
+        }
+
+        /// Takes an amount off the position of a bettor on an outcome, the whole position if
+        /// `None`, removing it once it is empty. Returns the amount taken.
+        fn take_from_position(
+            match_id: MatchId,
+            betting_match: &mut MatchOf<T>,
+            who: &T::AccountId,
+            result: OutcomeIndex,
+            amount: Option<BalanceOf<T>>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let amount = <Bets<T>>::try_mutate_exists(
+                (match_id, who, result),
+                |maybe_bet| -> Result<BalanceOf<T>, DispatchError> {
+                    let bet = maybe_bet.as_mut().ok_or(Error::<T>::NoBet)?;
+                    let amount = amount.unwrap_or(bet.amount);
+                    ensure!(amount <= bet.amount, Error::<T>::AmountExceedsBet);
+                    bet.amount -= amount;
+                    if bet.amount.is_zero() {
+                        *maybe_bet = None;
+                        betting_match.bet_count -= 1;
+                    }
+                    Ok(amount)
+                },
+            )?;
+            if let Some(total) = betting_match.total_on_mut(result) {
+                *total -= amount;
+            }
+            Ok(amount)
+        }
+
+        /// Ensures the shares of an outcome of a match can be traded with its market maker, and
+        /// returns both.
+        fn ensure_trading(
+            match_id: MatchId,
+            outcome: OutcomeIndex,
+        ) -> Result<(MatchOf<T>, MarketMaker<BalanceOf<T>>), DispatchError> {
+            let betting_match = <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            let maker = <MarketMakers<T>>::get(match_id).ok_or(Error::<T>::NoMarketMaker)?;
+            ensure!(
+                betting_match.has_outcome(outcome),
+                Error::<T>::UnknownOutcome
+            );
+            Self::ensure_betting_open(match_id, &betting_match)?;
+            Ok((betting_match, maker))
+        }
+
+        /// Ensures a match takes bets: before it starts, or until it ends if it is played
+        /// in-play and the betting is not suspended. Returns its in-play state once it has
+        /// started.
+        fn ensure_betting_open(
+            match_id: MatchId,
+            betting_match: &MatchOf<T>,
+        ) -> Result<Option<InPlayOf<T>>, DispatchError> {
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            let in_play = <InPlayMarkets<T>>::get(match_id);
+            if let Some(until) = in_play.as_ref().and_then(|in_play| in_play.suspended_until) {
+                ensure!(current_block_number >= until, Error::<T>::BettingSuspended);
+            }
+            if current_block_number < betting_match.start {
+                return Ok(None);
+            }
+
+            let in_play = in_play.ok_or(Error::<T>::MatchHasStarted)?;
+            ensure!(
+                current_block_number < betting_match.start + betting_match.length,
+                Error::<T>::TimeMatchOver
+            );
+            Ok(Some(in_play))
+        }
+
+        /// Returns the part of a bet placed now on a match being played that is discounted from
+        /// the winnings: the part of the match already played, rounded down.
+        fn in_play_discount(betting_match: &MatchOf<T>, amount: BalanceOf<T>) -> BalanceOf<T> {
+            let played = <frame_system::Pallet<T>>::block_number()
+                .saturating_sub(betting_match.start)
+                .min(betting_match.length);
+            multiply_by_rational_with_rounding(
+                amount.saturated_into(),
+                played.saturated_into(),
+                betting_match.length.saturated_into(),
+                Rounding::Down,
+            )
+            .map(|discount| discount.saturated_into())
+            .unwrap_or_else(Zero::zero)
+        }
+
+        /// Returns the cost of buying shares of an outcome of a match from its market maker.
+        pub(crate) fn buy_cost(
+            betting_match: &MatchOf<T>,
+            maker: &MarketMaker<BalanceOf<T>>,
+            outcome: OutcomeIndex,
+            shares: BalanceOf<T>,
+        ) -> Option<BalanceOf<T>> {
+            lmsr::buy_cost(
+                &Self::outstanding_shares(betting_match),
+                maker.liquidity.saturated_into(),
+                outcome as usize,
+                shares.saturated_into(),
+            )
+            .map(|cost| cost.saturated_into())
+        }
+
+        /// Returns the outstanding shares of every outcome of a match.
+        fn outstanding_shares(betting_match: &MatchOf<T>) -> Vec<u128> {
+            betting_match
+                .outcomes
+                .iter()
+                .map(|outcome| outcome.total.saturated_into())
+                .collect()
+        }
+
+        /// Returns a hash of match specs.
+        ///
+        /// **Parameters:**
+        ///   * `betting_match` – Match specs.
+        pub fn get_match_hash(betting_match: MatchOf<T>) -> T::Hash {
+            let names: Vec<_> = betting_match
+                .outcomes
+                .into_iter()
+                .map(|outcome| outcome.name)
+                .collect();
+            let entropy = match (betting_match.parent, betting_match.teams) {
+                (None, None) => {
+                    (names, betting_match.start, betting_match.length).using_encoded(blake2_256)
+                }
+                // Matches between registered teams are told apart by their teams and their start
+                (None, Some((team1, team2))) => {
+                    (team1.min(team2), team1.max(team2), betting_match.start)
+                        .using_encoded(blake2_256)
+                }
+                // Derived markets are told apart by their match and their market
+                (Some(parent), _) => (
+                    names,
+                    betting_match.start,
+                    betting_match.length,
+                    parent,
+                    betting_match.market,
+                )
+                    .using_encoded(blake2_256),
+            };
+            Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+                .expect("infinite length input; no invalid inputs for type; qed")
+        }
+
+        /// Voids a match, so that every bet is refunded once the void is final.
+        fn do_void_match(match_id: MatchId) -> DispatchResult {
+            let mut betting_match =
+                <Matches<T>>::take(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number > (betting_match.start + betting_match.length),
+                Error::<T>::TimeMatchNotOver
+            );
+            ensure!(!betting_match.has_result(), Error::<T>::ResultAlreadySet);
+
+            betting_match.voided = true;
+            let dispute_end = current_block_number + T::DisputePeriod::get();
+            betting_match.dispute_end = Some(dispute_end);
+            Self::enqueue(dispute_end, match_id);
+            <Matches<T>>::insert(match_id, betting_match);
+
+            Self::deposit_event(Event::MatchVoided(match_id));
+            Ok(())
+        }
+
+        /// Returns the markets derived from a match that have no result yet.
+        fn unresolved_markets(match_id: MatchId) -> Vec<MatchId> {
+            <DerivedMarkets<T>>::get(match_id)
+                .into_iter()
+                .filter(|market_id| {
+                    <Matches<T>>::get(market_id)
+                        .map_or(false, |betting_match| !betting_match.has_result())
+                })
+                .collect()
+        }
+
+        /// Ensures the result of a match can no longer change and returns how its pot is shared.
+        fn ensure_result_final(
+            match_id: MatchId,
+            betting_match: &MatchOf<T>,
+        ) -> Result<Settlement, DispatchError> {
+            let settlement = betting_match
+                .settlement()
+                .ok_or(Error::<T>::MatchNotResult)?;
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                betting_match
+                    .dispute_end
+                    .map_or(true, |dispute_end| current_block_number >= dispute_end),
+                Error::<T>::DisputePeriodNotOver
+            );
+            ensure!(
+                !<Disputes<T>>::contains_key(match_id),
+                Error::<T>::MatchDisputed
+            );
+            Ok(settlement)
+        }
+
+        /// Returns the block after which anyone can refund a match that has no result.
+        fn result_deadline(betting_match: &MatchOf<T>) -> T::BlockNumber {
+            betting_match.start + betting_match.length + T::ResultTimeout::get()
+        }
+
+        /// Queues a match to be finalised by `on_idle` once a block is reached.
+        pub(crate) fn enqueue(block: T::BlockNumber, match_id: MatchId) {
+            <SettlementQueue<T>>::insert(block, match_id, ());
+            <SettlementCursor<T>>::mutate(|cursor| {
+                *cursor = Some(cursor.map_or(block, |cursor| cursor.min(block)))
+            });
+        }
+
+        /// Walks the settlement queue up to the current block, finalising every queued match
+        /// within the remaining weight. The first match whose bets do not all fit is paid as far
+        /// as the weight goes and finalised in a later block. Returns the weight consumed.
+        fn finalise_queued(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let mut consumed = T::DbWeight::get().reads(1);
+            let mut cursor = match <SettlementCursor<T>>::get() {
+                Some(cursor) => cursor,
+                None => return consumed,
+            };
+            // The cursor is written back once the queue has been processed
+            consumed = consumed.saturating_add(T::DbWeight::get().writes(1));
+            let advance = T::DbWeight::get().reads_writes(1, 1);
+
+            while cursor <= now {
+                match <SettlementQueue<T>>::iter_key_prefix(cursor).next() {
+                    Some(match_id) => {
+                        // Every bet of the match left is paid or refunded
+                        let bet_count = <Matches<T>>::get(match_id)
+                            .map_or(0, |betting_match| betting_match.bet_count);
+                        consumed = consumed.saturating_add(T::DbWeight::get().reads(1));
+                        let step = T::WeightInfo::finalise_queued_match(bet_count);
+                        if consumed.saturating_add(step).any_gt(remaining_weight) {
+                            // A match with too many bets for the block is paid a page at a time
+                            let page = Self::bets_within(remaining_weight.saturating_sub(consumed));
+                            if page > 0 {
+                                consumed = consumed
+                                    .saturating_add(T::WeightInfo::finalise_queued_match(page));
+                                if with_storage_layer(|| Self::finalise_page(match_id, page))
+                                    .is_err()
+                                {
+                                    <SettlementQueue<T>>::remove(cursor, match_id);
+                                }
+                            }
+                            break;
+                        }
+                        consumed = consumed.saturating_add(step);
+                        <SettlementQueue<T>>::remove(cursor, match_id);
+                        // A match that can not be finalised yet is queued again when it can
+                        let _ = with_storage_layer(|| Self::finalise_match(match_id));
+                    }
+                    None => {
+                        if consumed.saturating_add(advance).any_gt(remaining_weight) {
+                            break;
+                        }
+                        consumed = consumed.saturating_add(advance);
+                        cursor += One::one();
+                    }
+                }
+            }
+            <SettlementCursor<T>>::put(cursor);
+
+            consumed
+        }
+
+        /// Returns how many bets of a queued match can be paid with an amount of weight.
+        fn bets_within(weight: Weight) -> u32 {
+            let base = T::WeightInfo::finalise_queued_match(0);
+            if base.any_gt(weight) {
+                return 0;
+            }
+            let per_bet = T::WeightInfo::finalise_queued_match(1).saturating_sub(base);
+            (weight.saturating_sub(base).ref_time() / per_bet.ref_time().max(1)).saturated_into()
+        }
+
+        /// Pays or refunds up to `limit` bets of a queued match, leaving the rest for a later
+        /// block. A match that never got a result is voided first, so it can no longer get one
+        /// once some of its bets are refunded.
+        fn finalise_page(match_id: MatchId, limit: u32) -> DispatchResult {
+            let mut betting_match =
+                <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            if !betting_match.has_result() {
+                let current_block_number = <frame_system::Pallet<T>>::block_number();
+                ensure!(
+                    current_block_number >= Self::result_deadline(&betting_match),
+                    Error::<T>::ResultTimeoutNotOver
+                );
+                betting_match.voided = true;
+                Self::deposit_event(Event::MatchTimedOut(
+                    match_id,
+                    betting_match.creator.clone(),
+                    Zero::zero(),
+                ));
+            }
+            let settlement = Self::ensure_result_final(match_id, &betting_match)?;
+            Self::pay_bets(match_id, &mut betting_match, settlement, limit)?;
+            <Matches<T>>::insert(match_id, betting_match);
+            Ok(())
+        }
+
+        /// Advances the tournaments whose round is over with the weight left in the block.
+        fn advance_rounds_over(remaining_weight: Weight) -> Weight {
+            let mut consumed = T::DbWeight::get().reads(1);
+            let step = T::WeightInfo::advance_tournament(T::MaxTournamentMatches::get());
+            while let Some(tournament_id) = <RoundsOver<T>>::iter_keys().next() {
+                if consumed.saturating_add(step).any_gt(remaining_weight) {
+                    break;
+                }
+                consumed = consumed.saturating_add(step);
+                // A tournament that can not be advanced is left to `advance_tournament`
+                <RoundsOver<T>>::remove(tournament_id);
+                let _ = with_storage_layer(|| Self::do_advance_tournament(tournament_id));
+            }
+            consumed
+        }
+
+        /// Schedules the settlement of a match at a block.
+        fn schedule_settlement_at(match_id: MatchId, when: T::BlockNumber) -> DispatchResult {
+            let call =
+                T::Preimages::bound(CallOf::<T>::from(Call::<T>::settle_scheduled { match_id }))?;
+            T::Scheduler::schedule(
+                DispatchTime::At(when),
+                None,
+                LOWEST_PRIORITY,
+                frame_system::RawOrigin::Root.into(),
+                call,
+            )?;
+            <ScheduledSettlements<T>>::insert(match_id, when);
+
+            Self::deposit_event(Event::SettlementScheduled(match_id, when));
+            Ok(())
+        }
+
+        /// Settles a match whose result is final, or refunds it if it never got a result.
+        fn finalise_match(match_id: MatchId) -> DispatchResult {
+            let betting_match = <Matches<T>>::get(match_id).ok_or(Error::<T>::MatchDoesNotExist)?;
+            if betting_match.has_result() {
+                return Self::do_distribute_winnings(match_id, betting_match);
+            }
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                current_block_number >= Self::result_deadline(&betting_match),
+                Error::<T>::ResultTimeoutNotOver
+            );
+            let creator = betting_match.creator.clone();
+            <Matches<T>>::remove(match_id);
+            Self::do_refund_match(match_id, betting_match)?;
+            Self::deposit_event(Event::MatchTimedOut(match_id, creator, Zero::zero()));
+            Ok(())
+        }
+
+        /// Pays the winners of a match that have not claimed yet and deletes it.
+        fn do_distribute_winnings(
+            match_id: MatchId,
+            mut betting_match: MatchOf<T>,
+        ) -> DispatchResult {
+            let settlement = Self::ensure_result_final(match_id, &betting_match)?;
+            Self::pay_bets(match_id, &mut betting_match, settlement, u32::MAX)?;
+            Self::close_settled(match_id, betting_match, settlement)
+        }
+
+        /// Pays or refunds up to `limit` bets of a match whose result is final that have not been
+        /// claimed yet, and deletes them along with the losing bets.
+        fn pay_bets(
+            match_id: MatchId,
+            betting_match: &mut MatchOf<T>,
+            settlement: Settlement,
+            limit: u32,
+        ) -> DispatchResult {
+            let maker = <MarketMakers<T>>::get(match_id);
+            let book = <Books<T>>::get(match_id);
+            if let Some(maker) = &maker {
+                Self::settle_shares(match_id, betting_match, maker)?;
+            }
+            let (total_bet, total_winners) = Self::bet_totals(match_id, betting_match, settlement);
+            let shared = maker.is_none() && book.is_none() && !total_winners.is_zero();
+            let winners_pot = if shared {
+                Self::settle(match_id, betting_match, total_bet)?
+            } else {
+                Zero::zero()
+            };
+            // A match being paid can no longer be cancelled
+            betting_match.settled_pot.get_or_insert_with(Zero::zero);
+
+            let pot = Self::pot_account(match_id);
+            for ((bettor, outcome), bet) in
+                <Bets<T>>::drain_prefix((match_id,)).take(limit as usize)
+            {
+                betting_match.bet_count = betting_match.bet_count.saturating_sub(1);
+                if bet.claimed {
+                    continue;
+                }
+                if let Some(maker) = &maker {
+                    let amount =
+                        Self::redemption(betting_match, maker, settlement, outcome, bet.amount)?;
+                    T::Currency::transfer(&pot, &bettor, amount, AllowDeath)?;
+                } else if settlement == Settlement::Push {
+                    Self::transfer_asset(betting_match.asset, &pot, &bettor, bet.amount)?;
+                } else if let Some(book) = &book {
+                    if settlement.pays(outcome) {
+                        Self::pay_at_odds(
+                            match_id,
+                            betting_match,
+                            book,
+                            settlement,
+                            &bettor,
+                            outcome,
+                            bet.amount,
+                        )?;
+                    }
+                } else if shared {
+                    let (refund, amount_won) = Self::payout(
+                        settlement,
+                        outcome,
+                        bet.amount,
+                        Self::weighted_amount(match_id, &bettor, outcome, bet.amount),
+                        total_winners,
+                        winners_pot,
+                    );
+                    Self::transfer_asset(betting_match.asset, &pot, &bettor, refund + amount_won)?;
+                    betting_match.paid_winnings += amount_won;
+                } else {
+                    // Nobody bet on the result: the bets are refunded following the
+                    // `NoWinnerPolicy`, and on a half win the half not at stake goes back
+                    let refund = match (settlement, T::NoWinnerPolicy::get()) {
+                        (_, NoWinnerPolicy::Refund) => bet.amount,
+                        (Settlement::HalfWin(_), _) => bet.amount - Self::half(bet.amount),
+                        _ => Zero::zero(),
+                    };
+                    Self::transfer_asset(betting_match.asset, &pot, &bettor, refund)?;
+                    betting_match.paid_winnings += refund;
+                }
+                if settlement.pays(outcome) {
+                    betting_match.claimed_stakes += bet.amount;
+                }
+            }
+            Ok(())
+        }
+
+        /// Deletes a match whose bets have all been paid. The rounding remainder of a pot shared
+        /// by the winners goes to the creator, and the pot of a match without winners is settled
+        /// following the `NoWinnerPolicy`.
+        fn close_settled(
+            match_id: MatchId,
+            betting_match: MatchOf<T>,
+            settlement: Settlement,
+        ) -> DispatchResult {
+            if <MarketMakers<T>>::contains_key(match_id) {
+                return Self::close_match(match_id, betting_match);
+            }
+            if settlement == Settlement::Push {
+                return Self::settle_push(match_id, betting_match);
+            }
+            if <Books<T>>::contains_key(match_id) {
+                return Self::close_match(match_id, betting_match);
+            }
+            let (_, total_winners) = Self::bet_totals(match_id, &betting_match, settlement);
+            if total_winners.is_zero() {
+                return Self::settle_without_winners(match_id, betting_match);
+            }
+
+            // The remainder left by the rounding goes to the creator
+            let winners_pot = betting_match.settled_pot.unwrap_or_else(Zero::zero);
+            let dust = winners_pot.saturating_sub(betting_match.paid_winnings);
+            Self::transfer_asset(
+                betting_match.asset,
+                &Self::pot_account(match_id),
+                &betting_match.creator,
+                dust,
+            )?;
+
+            Self::close_match(match_id, betting_match)
+        }
+
+        /// Returns the pot of a match shared by the winners, before fees, and the amount bet on
+        /// the winning outcome, less the discounts of the bets placed in-play. The half of every
+        /// bet refunded by a half win is left out.
+        fn bet_totals(
+            match_id: MatchId,
+            betting_match: &MatchOf<T>,
+            settlement: Settlement,
+        ) -> (BalanceOf<T>, BalanceOf<T>) {
+            let total = betting_match.total();
+            let staked = match settlement {
+                Settlement::HalfWin(_) => total - Self::half(total),
+                Settlement::Win(_) | Settlement::Push => total,
+            };
+            let in_play = <InPlayMarkets<T>>::get(match_id);
+            let total_winners = settlement.winner().map_or_else(Zero::zero, |winner| {
+                let discount = in_play
+                    .as_ref()
+                    .and_then(|in_play| in_play.discounts.get(winner as usize).copied())
+                    .unwrap_or_else(Zero::zero);
+                betting_match.total_on(winner).saturating_sub(discount)
+            });
+            (betting_match.rollover + staked, total_winners)
+        }
+
+        /// Returns the amount of a position that shares the winnings, less the discount of the
+        /// bets placed in-play.
+        fn weighted_amount(
+            match_id: MatchId,
+            who: &T::AccountId,
+            result: OutcomeIndex,
+            amount: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            amount.saturating_sub(<InPlayDiscounts<T>>::get((match_id, who, result)))
+        }
+
+        /// Returns the amount bet by the bettors who get something back once a match is
+        /// settled.
+        fn paid_stakes(betting_match: &MatchOf<T>, settlement: Settlement) -> BalanceOf<T> {
+            match settlement {
+                Settlement::Win(winner) => betting_match.total_on(winner),
+                Settlement::HalfWin(_) | Settlement::Push => betting_match.total(),
+            }
+        }
+
+        /// Returns the part of a bet on an outcome that is refunded and the winnings it gets from
+        /// the pot left for the winners, shared by the weighted amount of the bet.
+        fn payout(
+            settlement: Settlement,
+            result: OutcomeIndex,
+            amount: BalanceOf<T>,
+            weighted_amount: BalanceOf<T>,
+            total_winners: BalanceOf<T>,
+            winners_pot: BalanceOf<T>,
+        ) -> (BalanceOf<T>, BalanceOf<T>) {
+            let refund = match settlement {
+                Settlement::HalfWin(_) => Self::half(amount),
+                Settlement::Win(_) => Zero::zero(),
+                Settlement::Push => amount,
+            };
+            let amount_won = if settlement.winner() == Some(result) {
+                Self::winnings(weighted_amount, total_winners, winners_pot)
+            } else {
+                Zero::zero()
+            };
+            (refund, amount_won)
+        }
+
+        /// Claims the positions of a bettor paid by a match, paying each of them with `pay`, and
+        /// deletes the match once every position paid has been claimed.
+        fn claim_positions(
+            match_id: MatchId,
+            mut betting_match: MatchOf<T>,
+            settlement: Settlement,
+            who: T::AccountId,
+            mut pay: impl FnMut(
+                &mut MatchOf<T>,
+                &T::AccountId,
+                OutcomeIndex,
+                BalanceOf<T>,
+            ) -> Result<BalanceOf<T>, DispatchError>,
+        ) -> DispatchResult {
+            let positions: Vec<_> = <Bets<T>>::iter_prefix((match_id, who.clone()))
+                .filter(|(result, bet)| settlement.pays(*result) && !bet.claimed)
+                .collect();
+            ensure!(!positions.is_empty(), Error::<T>::NothingToClaim);
+
+            let mut amount_paid: BalanceOf<T> = Zero::zero();
+            for (result, mut bet) in positions {
+                amount_paid += pay(&mut betting_match, &who, result, bet.amount)?;
+                bet.claimed = true;
+                betting_match.claimed_stakes += bet.amount;
+                <Bets<T>>::insert((match_id, &who, result), bet);
+            }
+
+            Self::deposit_event(Event::WinningsClaimed(match_id, who, amount_paid));
+
+            if betting_match.claimed_stakes == Self::paid_stakes(&betting_match, settlement) {
+                Self::close_match(match_id, betting_match)
+            } else {
+                <Matches<T>>::insert(match_id, betting_match);
+                Ok(())
+            }
+        }
+
+        /// Pays a bet on a fixed-odds match: the stake comes back from the pot, with the
+        /// winnings at the posted odds out of the bond of the creator. Returns the amount paid.
+        fn pay_at_odds(
+            match_id: MatchId,
+            betting_match: &mut MatchOf<T>,
+            book: &BookOf<T>,
+            settlement: Settlement,
+            bettor: &T::AccountId,
+            result: OutcomeIndex,
+            amount: BalanceOf<T>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let refund = match settlement {
+                Settlement::HalfWin(_) => Self::half(amount),
+                Settlement::Win(_) => Zero::zero(),
+                Settlement::Push => amount,
+            };
+            let (stake, amount_won) = if settlement.winner() == Some(result) {
+                (amount, book.winnings(result, amount - refund))
+            } else {
+                (refund, Zero::zero())
+            };
+
+            Self::transfer_asset(None, &Self::pot_account(match_id), bettor, stake)?;
+            T::Currency::repatriate_reserved(
+                &betting_match.creator,
+                bettor,
+                amount_won,
+                BalanceStatus::Free,
+            )?;
+            betting_match.paid_winnings += amount_won;
+            Ok(stake + amount_won)
+        }
+
+        /// Moves the subsidy of the market maker of a match to its pot the first time it is
+        /// called, so that the pot can redeem every share.
+        fn settle_shares(
+            match_id: MatchId,
+            betting_match: &mut MatchOf<T>,
+            maker: &MarketMaker<BalanceOf<T>>,
+        ) -> DispatchResult {
+            if betting_match.settled_pot.is_none() {
+                T::Currency::repatriate_reserved(
+                    &betting_match.creator,
+                    &Self::pot_account(match_id),
+                    maker.subsidy,
+                    BalanceStatus::Free,
+                )?;
+                betting_match.settled_pot = Some(maker.subsidy);
+            }
+            Ok(())
+        }
+
+        /// Returns what shares of an outcome are redeemed for: one unit for every share of the
+        /// winning outcome. A void match redeems them at the price quoted by its market maker.
+        fn redemption(
+            betting_match: &MatchOf<T>,
+            maker: &MarketMaker<BalanceOf<T>>,
+            settlement: Settlement,
+            outcome: OutcomeIndex,
+            shares: BalanceOf<T>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            match settlement {
+                Settlement::Win(winner) if winner == outcome => Ok(shares),
+                Settlement::Win(_) => Ok(Zero::zero()),
+                // A match with a market maker has no score, so it is only won or voided
+                Settlement::HalfWin(_) | Settlement::Push => lmsr::value(
+                    &Self::outstanding_shares(betting_match),
+                    maker.liquidity.saturated_into(),
+                    outcome as usize,
+                    shares.saturated_into(),
+                )
+                .map(|value| value.saturated_into())
+                .ok_or_else(|| Error::<T>::NoQuote.into()),
+            }
+        }
+
+        /// Returns half of an amount, rounded down.
+        fn half(amount: BalanceOf<T>) -> BalanceOf<T> {
+            amount / 2u32.into()
+        }
+
+        /// Takes the platform fee and the creator commission from the pot of a match the first
+        /// time it is called, and returns the pot left for the winners.
+        fn settle(
+            match_id: MatchId,
+            betting_match: &mut MatchOf<T>,
+            total_bet: BalanceOf<T>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            if let Some(winners_pot) = betting_match.settled_pot {
+                return Ok(winners_pot);
+            }
+
+            let platform_fee = T::PlatformFee::get() * total_bet;
+            let commission = betting_match.commission * total_bet;
+
+            Self::send_to_treasury(match_id, betting_match.asset, platform_fee)?;
+            Self::transfer_asset(
+                betting_match.asset,
+                &Self::pot_account(match_id),
+                &betting_match.creator,
+                commission,
+            )?;
+
+            let winners_pot = total_bet.saturating_sub(platform_fee + commission);
+            betting_match.settled_pot = Some(winners_pot);
+            Self::deposit_event(Event::MatchSettled(
+                match_id,
+                total_bet,
+                platform_fee,
+                commission,
+            ));
+            Ok(winners_pot)
+        }
+
+        /// Returns the share of the pot won by a winning bet, weighted by its amount.
+        /// The share is computed exactly and rounded down: `amount * total_bet / total_winners`.
+        pub(crate) fn winnings(
+            amount: BalanceOf<T>,
+            total_winners: BalanceOf<T>,
+            total_bet: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            multiply_by_rational_with_rounding(
+                amount.saturated_into(),
+                total_bet.saturated_into(),
+                total_winners.saturated_into(),
+                Rounding::Down,
+            )
+            .map(|amount_won| amount_won.saturated_into())
+            .unwrap_or_else(Zero::zero)
+        }
+
+        /// Settles the pot of a match where nobody bet on the result following the
+        /// `NoWinnerPolicy` once every bet has been refunded by `pay_bets`, and deletes the match.
+        fn settle_without_winners(match_id: MatchId, betting_match: MatchOf<T>) -> DispatchResult {
+            // The stakes refunded have already left the pot
+            let total_bet = (betting_match.rollover + betting_match.total())
+                .saturating_sub(betting_match.paid_winnings);
+            match T::NoWinnerPolicy::get() {
+                NoWinnerPolicy::Refund => Self::refund_pot(match_id, &betting_match)?,
+                NoWinnerPolicy::Rollover => {
+                    Self::roll_over(match_id, &betting_match, total_bet)?;
+                    Self::deposit_event(Event::PotRolledOver(
+                        match_id,
+                        betting_match.creator.clone(),
+                        total_bet,
+                    ));
+                }
+                NoWinnerPolicy::Treasury => {
+                    Self::send_to_treasury(match_id, betting_match.asset, total_bet)?;
+                    Self::deposit_event(Event::PotSentToTreasury(match_id, total_bet));
+                }
+            }
+
+            Self::close_match(match_id, betting_match)
+        }
+
+        /// Deletes a match where nobody wins or loses once every bet has been refunded.
+        fn settle_push(match_id: MatchId, betting_match: MatchOf<T>) -> DispatchResult {
+            Self::refund_pot(match_id, &betting_match)?;
+            Self::close_match(match_id, betting_match)
+        }
+
+        /// Finishes the refund of a settled match once every bet has been refunded. A rolled
+        /// over pot has no bettor to be refunded to, so it goes to the next match of the creator
+        /// again.
+        fn refund_pot(match_id: MatchId, betting_match: &MatchOf<T>) -> DispatchResult {
+            if !betting_match.rollover.is_zero() {
+                Self::roll_over(match_id, betting_match, betting_match.rollover)?;
+            }
+            Self::deposit_event(Event::PotRefunded(match_id, betting_match.total()));
+            Ok(())
+        }
+
+        /// Transfers an amount of the asset of a match, the native currency if `None`.
+        fn transfer_asset(
+            asset: Option<AssetIdOf<T>>,
+            source: &T::AccountId,
+            dest: &T::AccountId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            match asset {
+                Some(asset) => {
+                    <T::Assets as fungibles::Transfer<_>>::transfer(
+                        asset, source, dest, amount, false,
+                    )?;
+                    Ok(())
+                }
+                None => T::Currency::transfer(source, dest, amount, AllowDeath),
+            }
+        }
+
+        /// Sends an amount of the pot of a match to the `Treasury`, or to the `AssetTreasury`
+        /// account if the match is bet in an asset.
+        fn send_to_treasury(
+            match_id: MatchId,
+            asset: Option<AssetIdOf<T>>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let pot = Self::pot_account(match_id);
+            match asset {
+                Some(_) => Self::transfer_asset(asset, &pot, &T::AssetTreasury::get(), amount),
+                None => {
+                    let imbalance =
+                        T::Currency::withdraw(&pot, amount, WithdrawReasons::TRANSFER, AllowDeath)?;
+                    T::Treasury::on_unbalanced(imbalance);
+                    Ok(())
+                }
+            }
+        }
+
+        /// Keeps an amount of the pot of a match for the next match of its creator in the same
+        /// asset. The pot account of the first match rolled over holds every later rollover until
+        /// it is taken, so it always has its existential deposit.
+        fn roll_over(
+            match_id: MatchId,
+            betting_match: &MatchOf<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            <Rollovers<T>>::try_mutate(
+                &betting_match.creator,
+                betting_match.asset,
+                |maybe_rollover| -> DispatchResult {
+                    match maybe_rollover {
+                        Some((holder_id, rollover)) => {
+                            Self::transfer_asset(
+                                betting_match.asset,
+                                &Self::pot_account(match_id),
+                                &Self::pot_account(*holder_id),
+                                amount,
+                            )?;
+                            *rollover += amount;
+                        }
+                        None => *maybe_rollover = Some((match_id, amount)),
+                    }
+                    Ok(())
+                },
+            )
+        }
+
+        /// Sends whatever is left in the pot account of a match, its seed included, to the
+        /// creator so the account is reaped.
+        fn sweep_pot(
+            match_id: MatchId,
+            asset: Option<AssetIdOf<T>>,
+            creator: &T::AccountId,
+        ) -> DispatchResult {
+            let pot = Self::pot_account(match_id);
+            if let Some(asset) = asset {
+                Self::transfer_asset(Some(asset), &pot, creator, T::Assets::balance(asset, &pot))?;
+            }
+            T::Currency::transfer(&pot, creator, T::Currency::free_balance(&pot), AllowDeath)
+        }
+
+        /// Sweeps the pot account of a finished match, unless it holds a rollover of the creator.
+        fn release_pot(match_id: MatchId, betting_match: &MatchOf<T>) -> DispatchResult {
+            let is_holder = <Rollovers<T>>::get(&betting_match.creator, betting_match.asset)
+                .map_or(false, |(holder_id, _)| holder_id == match_id);
+            if is_holder {
+                return Ok(());
+            }
+            Self::sweep_pot(match_id, betting_match.asset, &betting_match.creator)
+        }
+
+        /// Forgets the hash of a deleted match, unless it is the hash of another match.
+        fn remove_match_hash(match_id: MatchId, betting_match: &MatchOf<T>) {
+            let match_hash = Self::get_match_hash(betting_match.clone());
+            if <MatchHashes<T>>::get(&match_hash) == Some(match_id) {
+                <MatchHashes<T>>::remove(match_hash);
+            }
+        }
+
+        /// Deletes a match without settling it, refunding every bet, the bonds of any open
+        /// dispute and the deposit of its creator.
+        fn do_refund_match(match_id: MatchId, mut betting_match: MatchOf<T>) -> DispatchResult {
+            Self::remove_match_hash(match_id, &betting_match);
+            <MatchesByCreator<T>>::remove(&betting_match.creator, match_id);
+            <ScheduledSettlements<T>>::remove(match_id);
+            Self::unlink_markets(match_id, &betting_match);
+            Self::remove_in_play(match_id);
+            Self::record_fixture(match_id, Settlement::Push);
+            Self::settle_legs(match_id, Settlement::Push)?;
+
+            // Refund every bet, or redeem every share as if the match was voided
+            let pot = Self::pot_account(match_id);
+            let maker = <MarketMakers<T>>::get(match_id);
+            if let Some(maker) = &maker {
+                Self::settle_shares(match_id, &mut betting_match, maker)?;
+            }
+            for ((bettor, outcome), bet) in <Bets<T>>::drain_prefix((match_id,)) {
+                let amount = match &maker {
+                    Some(maker) => Self::redemption(
+                        &betting_match,
+                        maker,
+                        Settlement::Push,
+                        outcome,
+                        bet.amount,
+                    )?,
+                    None => bet.amount,
+                };
+                Self::transfer_asset(betting_match.asset, &pot, &bettor, amount)?;
+            }
+
+            // The rolled over pot goes back to the creator's next match
+            if !betting_match.rollover.is_zero() {
+                Self::roll_over(match_id, &betting_match, betting_match.rollover)?;
+            }
+            Self::release_pot(match_id, &betting_match)?;
+
+            // Return the bonds of any open dispute
+            for (disputer, bond) in <Disputes<T>>::take(match_id) {
+                T::Currency::unreserve(&disputer, bond);
+            }
+
+            // Unreserve the initial deposit of the creator.
+            T::Currency::unreserve(&betting_match.creator, betting_match.deposit);
+            Self::release_reserves(match_id, &betting_match);
+            Ok(())
+        }
+
+        /// Deletes a settled match, releases its pot and returns the deposit to its creator.
+        fn close_match(match_id: MatchId, betting_match: MatchOf<T>) -> DispatchResult {
+            Self::remove_match_hash(match_id, &betting_match);
+            <MatchesByCreator<T>>::remove(&betting_match.creator, match_id);
+            <ScheduledSettlements<T>>::remove(match_id);
+            Self::unlink_markets(match_id, &betting_match);
+            <Matches<T>>::remove(match_id);
+            let _ = <Bets<T>>::clear_prefix((match_id,), T::MaxBetsPerMatch::get(), None);
+            Self::remove_in_play(match_id);
+            let settlement = betting_match.settlement().unwrap_or(Settlement::Push);
+            Self::record_fixture(match_id, settlement);
+            Self::settle_legs(match_id, settlement)?;
+
+            Self::release_pot(match_id, &betting_match)?;
+
+            // Unreserve the initial deposit of the creator.
+            T::Currency::unreserve(&betting_match.creator, betting_match.deposit);
+            Self::release_reserves(match_id, &betting_match);
+
+            Self::deposit_event(Event::MatchClosed(match_id));
+            Ok(())
+        }
+
+        /// Unreserves what is left of the bond of a fixed-odds match once the winnings are paid,
+        /// and the subsidy of a market maker that has not been moved to the pot.
+        fn release_reserves(match_id: MatchId, betting_match: &MatchOf<T>) {
+            if let Some(book) = <Books<T>>::take(match_id) {
+                T::Currency::unreserve(
+                    &betting_match.creator,
+                    book.bond.saturating_sub(betting_match.paid_winnings),
+                );
+            }
+            if let Some(maker) = <MarketMakers<T>>::take(match_id) {
+                if betting_match.settled_pot.is_none() {
+                    T::Currency::unreserve(&betting_match.creator, maker.subsidy);
+                }
+            }
+        }
+
+        /// Settles the legs of the parlays on a deleted match, and the parlays they decide.
+        fn settle_legs(match_id: MatchId, settlement: Settlement) -> DispatchResult {
+            for parlay_id in <MatchParlays<T>>::take(match_id) {
+                if let Some(mut parlay) = <Parlays<T>>::get(parlay_id) {
+                    let lost = !Self::settle_leg(&mut parlay, match_id, settlement);
+                    Self::conclude_parlay(parlay_id, parlay, lost)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Records how a match settles the leg of a parlay on it, which is dropped if the match
+        /// is void. Returns `false` if the leg lost.
+        fn settle_leg(parlay: &mut ParlayOf<T>, match_id: MatchId, settlement: Settlement) -> bool {
+            let mut won = true;
+            for leg in parlay
+                .legs
+                .iter_mut()
+                .filter(|leg| leg.match_id == match_id && leg.state == LegState::Open)
+            {
+                match settlement {
+                    Settlement::Push => leg.state = LegState::Void,
+                    settlement if settlement.winner() == Some(leg.outcome) => {
+                        leg.state = LegState::Won
+                    }
+                    _ => won = false,
+                }
+            }
+            won
+        }
+
+        /// Pays a parlay once every leg has won or been voided, or pays its stake to the
+        /// bookmaker if a leg lost, and deletes it. Keeps it until then.
+        fn conclude_parlay(parlay_id: ParlayId, parlay: ParlayOf<T>, lost: bool) -> DispatchResult {
+            if !lost && parlay.legs.iter().any(|leg| leg.state == LegState::Open) {
+                <Parlays<T>>::insert(parlay_id, parlay);
+                return Ok(());
+            }
+
+            <Parlays<T>>::remove(parlay_id);
+            for leg in parlay.legs.iter() {
+                <MatchParlays<T>>::mutate_exists(leg.match_id, |maybe_parlay_ids| {
+                    if let Some(parlay_ids) = maybe_parlay_ids {
+                        parlay_ids.retain(|id| *id != parlay_id);
+                        if parlay_ids.is_empty() {
+                            *maybe_parlay_ids = None;
+                        }
+                    }
+                });
+            }
+
+            if lost {
+                T::Currency::repatriate_reserved(
+                    &parlay.bettor,
+                    &parlay.bookmaker,
+                    parlay.stake,
+                    BalanceStatus::Free,
+                )?;
+                T::Currency::unreserve(&parlay.bookmaker, parlay.liability);
+                Self::deposit_event(Event::ParlayLost(parlay_id, parlay.bettor));
+            } else {
+                let winnings = parlay.winnings().min(parlay.liability);
+                T::Currency::unreserve(&parlay.bettor, parlay.stake);
+                T::Currency::repatriate_reserved(
+                    &parlay.bookmaker,
+                    &parlay.bettor,
+                    winnings,
+                    BalanceStatus::Free,
+                )?;
+                T::Currency::unreserve(&parlay.bookmaker, parlay.liability - winnings);
+                Self::deposit_event(Event::ParlayWon(parlay_id, parlay.bettor, winnings));
+            }
+            Ok(())
+        }
+
+        /// Returns the fixtures of a league between a number of teams, as the round and the
+        /// indices of the two teams of every match. Every team plays once a round, one team
+        /// sitting every round out if their number is odd.
+        fn round_robin(teams: u32) -> Vec<(u32, u32, u32)> {
+            // Keep the first slot and rotate the others every round
+            let slots = teams + teams % 2;
+            let mut fixtures = Vec::new();
+            for round in 0..slots - 1 {
+                let team = |slot: u32| match slot {
+                    0 => 0,
+                    slot => (slot + round - 1) % (slots - 1) + 1,
+                };
+                for slot in 0..slots / 2 {
+                    let (home, away) = (team(slot), team(slots - 1 - slot));
+                    if home < teams && away < teams {
+                        fixtures.push((round, home, away));
+                    }
+                }
+            }
+            fixtures
+        }
+
+        /// Creates the match of a round of a tournament between two of its teams. A match
+        /// scheduled late starts on the next block.
+        fn schedule_fixture(
+            tournament_id: TournamentId,
+            tournament: &TournamentOf<T>,
+            round: u32,
+            home: u32,
+            away: u32,
+        ) -> Result<Fixture, DispatchError> {
+            let team = |index: u32| tournament.teams.get(index as usize).copied();
+            let (team1, team2) = team(home).zip(team(away)).ok_or(Error::<T>::UnknownTeam)?;
+            let mut names = Self::team_names(&[team1, team2])?;
+            if tournament.format == TournamentFormat::League {
+                names.push(DRAW.to_vec());
+            }
+
+            let current_block_number = <frame_system::Pallet<T>>::block_number();
+            let start = tournament
+                .start
+                .saturating_add(tournament.round_length.saturating_mul(round.into()))
+                .max(current_block_number + One::one());
+            let (mut betting_match, names) = Self::new_match(
+                tournament.organizer.clone(),
+                names,
+                start,
+                tournament.match_length,
+                tournament.commission,
+                None,
+            )?;
+            betting_match.teams = Some((team1, team2));
+            if tournament.format == TournamentFormat::League {
+                betting_match.market = Some(ScoreMarket::MatchResult);
+            }
+            let match_id = Self::insert_match(betting_match, names, true, Some(tournament_id))?;
+
+            Ok(Fixture {
+                match_id,
+                home,
+                away,
+                result: None,
+            })
+        }
+
+        /// Advances a tournament once every match of its current round has a final result.
+        fn do_advance_tournament(tournament_id: TournamentId) -> DispatchResult {
+            <RoundsOver<T>>::remove(tournament_id);
+            let mut tournament =
+                <Tournaments<T>>::get(tournament_id).ok_or(Error::<T>::TournamentDoesNotExist)?;
+            for fixture in tournament
+                .fixtures
+                .iter_mut()
+                .filter(|fixture| fixture.result.is_none())
+            {
+                let settlement = <Matches<T>>::get(fixture.match_id).and_then(|betting_match| {
+                    Self::ensure_result_final(fixture.match_id, &betting_match).ok()
+                });
+                fixture.result = Some(settlement.ok_or(Error::<T>::RoundNotOver)?);
+            }
+
+            match tournament.format {
+                TournamentFormat::League => {
+                    let winner = Self::league_leader(&tournament);
+                    Self::finish_tournament(tournament_id, tournament, winner);
+                }
+                TournamentFormat::Knockout => {
+                    let void = tournament
+                        .fixtures
+                        .iter()
+                        .any(|fixture| fixture.result == Some(Settlement::Push));
+                    if void {
+                        // Replay the void matches between the same teams in the same round, in
+                        // place so that the winners are still paired in the order of the bracket
+                        let round = tournament.rounds.saturating_sub(1);
+                        let mut fixtures = tournament.fixtures.clone();
+                        for fixture in fixtures
+                            .iter_mut()
+                            .filter(|fixture| fixture.result == Some(Settlement::Push))
+                        {
+                            *fixture = Self::schedule_fixture(
+                                tournament_id,
+                                &tournament,
+                                round,
+                                fixture.home,
+                                fixture.away,
+                            )?;
+                        }
+                        tournament.fixtures = fixtures;
+                        <Tournaments<T>>::insert(tournament_id, tournament);
+
+                        Self::deposit_event(Event::RoundScheduled(tournament_id, round));
+                        return Ok(());
+                    }
+
+                    let winners: Vec<_> = tournament
+                        .fixtures
+                        .iter()
+                        .map(|fixture| {
+                            if fixture.result.and_then(Settlement::winner)
+                                == Some(MatchResult::Team1Victory.index())
+                            {
+                                fixture.home
+                            } else {
+                                fixture.away
+                            }
+                        })
+                        .collect();
+                    if let [winner] = winners[..] {
+                        Self::finish_tournament(tournament_id, tournament, Some(winner));
+                        return Ok(());
+                    }
+
+                    let round = tournament.rounds;
+                    let mut fixtures = BoundedVec::default();
+                    for pair in winners.chunks(2) {
+                        let fixture = Self::schedule_fixture(
+                            tournament_id,
+                            &tournament,
+                            round,
+                            pair[0],
+                            pair[1],
+                        )?;
+                        fixtures
+                            .try_push(fixture)
+                            .map_err(|_| Error::<T>::TooManyMatches)?;
+                    }
+                    tournament.fixtures = fixtures;
+                    tournament.rounds = round + 1;
+                    <Tournaments<T>>::insert(tournament_id, tournament);
+
+                    Self::deposit_event(Event::RoundScheduled(tournament_id, round));
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns the team with the most points of a league, three for a win and one for a
+        /// draw, or `None` on a tie.
+        fn league_leader(tournament: &TournamentOf<T>) -> Option<OutcomeIndex> {
+            let mut points = vec![0u32; tournament.teams.len()];
+            for fixture in tournament.fixtures.iter() {
+                let (home, away) = (fixture.home as usize, fixture.away as usize);
+                match fixture.result.and_then(Settlement::winner) {
+                    Some(winner) if winner == MatchResult::Team1Victory.index() => {
+                        points[home] += 3
+                    }
+                    Some(winner) if winner == MatchResult::Team2Victory.index() => {
+                        points[away] += 3
+                    }
+                    Some(winner) if winner == MatchResult::Draw.index() => {
+                        points[home] += 1;
+                        points[away] += 1;
+                    }
+                    _ => {}
+                }
+            }
+
+            let most = points.iter().copied().max()?;
+            let mut leaders = (0..points.len()).filter(|team| points[*team] == most);
+            match (leaders.next(), leaders.next()) {
+                (Some(leader), None) => Some(leader as OutcomeIndex),
+                _ => None,
+            }
+        }
+
+        /// Settles the outright market of a tournament on its winner, or voids it without one,
+        /// and deletes the tournament.
+        fn finish_tournament(
+            tournament_id: TournamentId,
+            tournament: TournamentOf<T>,
+            winner: Option<OutcomeIndex>,
+        ) {
+            for fixture in tournament.fixtures.iter() {
+                <TournamentMatches<T>>::remove(fixture.match_id);
+            }
+            <Tournaments<T>>::remove(tournament_id);
+
+            // The outright market may have been refunded if the tournament finished late
+            let match_id = tournament.outright;
+            if let Some(mut outright) =
+                <Matches<T>>::get(match_id).filter(|outright| !outright.has_result())
+            {
+                outright.result = winner;
+                outright.voided = winner.is_none();
+                let dispute_end =
+                    <frame_system::Pallet<T>>::block_number() + T::DisputePeriod::get();
+                outright.dispute_end = Some(dispute_end);
+                Self::enqueue(dispute_end, match_id);
+                <Matches<T>>::insert(match_id, outright);
+
+                Self::deposit_event(match winner {
+                    Some(winner) => Event::MatchResult(match_id, winner),
+                    None => Event::MatchVoided(match_id),
+                });
+            }
+
+            Self::deposit_event(Event::TournamentFinished(tournament_id, winner));
+        }
+
+        /// Records the settlement of a deleted match in the fixture of its tournament, and
+        /// queues the tournament to be advanced once every fixture of its round is settled.
+        fn record_fixture(match_id: MatchId, settlement: Settlement) {
+            if let Some(tournament_id) = <TournamentMatches<T>>::take(match_id) {
+                <Tournaments<T>>::mutate(tournament_id, |maybe_tournament| {
+                    if let Some(tournament) = maybe_tournament {
+                        for fixture in tournament
+                            .fixtures
+                            .iter_mut()
+                            .filter(|fixture| fixture.match_id == match_id)
+                        {
+                            fixture.result = Some(settlement);
+                        }
+                        if tournament
+                            .fixtures
+                            .iter()
+                            .all(|fixture| fixture.result.is_some())
+                        {
+                            <RoundsOver<T>>::insert(tournament_id, ());
+                        }
+                    }
+                });
+            }
+        }
+
+        /// Forgets the in-play state of a deleted match and the discounts of its bets.
+        fn remove_in_play(match_id: MatchId) {
+            <InPlayMarkets<T>>::remove(match_id);
+            let _ =
+                <InPlayDiscounts<T>>::clear_prefix((match_id,), T::MaxBetsPerMatch::get(), None);
+        }
+
+        /// Removes a deleted match from the markets derived from its match, and forgets the
+        /// markets derived from it, which are settled on their own.
+        fn unlink_markets(match_id: MatchId, betting_match: &MatchOf<T>) {
+            <DerivedMarkets<T>>::remove(match_id);
+            if let Some(parent) = betting_match.parent {
+                <DerivedMarkets<T>>::mutate_exists(parent, |maybe_market_ids| {
+                    if let Some(market_ids) = maybe_market_ids {
+                        market_ids.retain(|market_id| *market_id != match_id);
+                        if market_ids.is_empty() {
+                            *maybe_market_ids = None;
+                        }
+                    }
+                });
+            }
+        }
+
+        /// The account holding the pot of a match.
+        pub fn pot_account(match_id: MatchId) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating(match_id)
         }
     }
 }
@@ -0,0 +1,155 @@
+//! Logarithmic market scoring rule (LMSR), the cost function the market makers price the shares
+//! of the outcomes of a match with.
+//!
+//! The outstanding shares `q` of a market maker with liquidity `b` cost
+//! `C(q) = b * ln(sum(exp(q_i / b)))`, and a trade costs the change of `C`. Over `n` outcomes a
+//! market maker loses at most `b * ln(n)`, the subsidy funded by the creator of the match.
+
+use sp_arithmetic::{
+    helpers_128bit::multiply_by_rational_with_rounding,
+    per_things::Rounding,
+    traits::{CheckedDiv, One, Saturating, Zero},
+    FixedI128, FixedPointNumber,
+};
+use sp_std::prelude::*;
+
+/// `ln(2)`.
+const LN_2: FixedI128 = FixedI128::from_inner(693_147_180_559_945_309);
+/// `exp(-1)`.
+const EXP_MINUS_1: FixedI128 = FixedI128::from_inner(367_879_441_171_442_321);
+/// `exp(-x)` is below the precision of `FixedI128` from this `x` on.
+const MAX_EXPONENT: i128 = 42;
+/// Terms of the series, enough for the precision of `FixedI128`.
+const SERIES_TERMS: i128 = 24;
+
+/// Returns `exp(-x)` for `x >= 0`.
+fn exp_neg(x: FixedI128) -> FixedI128 {
+    let whole = x.into_inner() / FixedI128::accuracy();
+    if whole >= MAX_EXPONENT {
+        return Zero::zero();
+    }
+
+    // Taylor series for the fractional part, then `exp(-1)` for every whole unit
+    let fraction = x.frac();
+    let mut term = FixedI128::one();
+    let mut sum = FixedI128::one();
+    for k in 1..SERIES_TERMS {
+        term = FixedI128::from_inner(term.saturating_mul(fraction).into_inner() / k);
+        sum = if k % 2 == 1 {
+            sum.saturating_sub(term)
+        } else {
+            sum.saturating_add(term)
+        };
+    }
+    (0..whole).fold(sum, |sum, _| sum.saturating_mul(EXP_MINUS_1))
+}
+
+/// Returns `ln(x)` for `x >= 1`.
+fn ln(x: FixedI128) -> FixedI128 {
+    // x = 2^k * y with 1 <= y < 2
+    let two = FixedI128::saturating_from_integer(2);
+    let mut k = 0;
+    let mut y = x;
+    while y >= two {
+        y = FixedI128::from_inner(y.into_inner() / 2);
+        k += 1;
+    }
+
+    // ln(y) = 2 * atanh(s) with s = (y - 1) / (y + 1) <= 1/3
+    let s = y
+        .saturating_sub(One::one())
+        .checked_div(&y.saturating_add(One::one()))
+        .unwrap_or_default();
+    let s_squared = s.saturating_mul(s);
+    let mut power = s;
+    let mut sum = FixedI128::zero();
+    for j in 0..SERIES_TERMS {
+        sum = sum.saturating_add(FixedI128::from_inner(power.into_inner() / (2 * j + 1)));
+        power = power.saturating_mul(s_squared);
+    }
+    LN_2.saturating_mul(FixedI128::saturating_from_integer(k))
+        .saturating_add(sum.saturating_add(sum))
+}
+
+/// Returns the most outstanding shares of an outcome `m` and `exp((q_i - m) / b)` for every
+/// outcome, `None` without liquidity. Factoring the largest exponent out keeps every `exp` at
+/// most one, and an exponent too large for `FixedI128` is far below its precision.
+fn exponentials(shares: &[u128], liquidity: u128) -> Option<(u128, Vec<FixedI128>)> {
+    if liquidity.is_zero() {
+        return None;
+    }
+    let max = shares.iter().copied().max()?;
+    let exponentials = shares
+        .iter()
+        .map(|q| {
+            FixedI128::checked_from_rational(max - q, liquidity).map_or_else(Zero::zero, exp_neg)
+        })
+        .collect();
+    Some((max, exponentials))
+}
+
+/// Returns `C(q) / b` for the outstanding shares of every outcome.
+fn cost(shares: &[u128], liquidity: u128) -> Option<FixedI128> {
+    let (max, exponentials) = exponentials(shares, liquidity)?;
+    let sum = exponentials
+        .into_iter()
+        .fold(FixedI128::zero(), |sum, x| sum.saturating_add(x));
+    Some(FixedI128::checked_from_rational(max, liquidity)?.saturating_add(ln(sum)))
+}
+
+/// Returns what an amount of shares of an outcome is worth at the price quoted for it,
+/// `exp(q_i / b) / sum(exp(q_j / b))`, rounded down.
+pub(crate) fn value(
+    shares: &[u128],
+    liquidity: u128,
+    outcome: usize,
+    amount: u128,
+) -> Option<u128> {
+    let (_, exponentials) = exponentials(shares, liquidity)?;
+    let sum = exponentials
+        .iter()
+        .fold(FixedI128::zero(), |sum, x| sum.saturating_add(*x));
+    let price = exponentials.get(outcome)?.checked_div(&sum)?;
+    price.checked_mul_int(amount)
+}
+
+/// Returns the cost of buying an amount of shares of an outcome, rounded up.
+pub(crate) fn buy_cost(
+    shares: &[u128],
+    liquidity: u128,
+    outcome: usize,
+    amount: u128,
+) -> Option<u128> {
+    let mut after = shares.to_vec();
+    let outstanding = after.get_mut(outcome)?;
+    *outstanding = outstanding.checked_add(amount)?;
+    let change = cost(&after, liquidity)?.saturating_sub(cost(shares, liquidity)?);
+    change.checked_mul_int(liquidity)?.checked_add(1)
+}
+
+/// Returns the proceeds of selling an amount of shares of an outcome, rounded down.
+pub(crate) fn sell_proceeds(
+    shares: &[u128],
+    liquidity: u128,
+    outcome: usize,
+    amount: u128,
+) -> Option<u128> {
+    let mut after = shares.to_vec();
+    let outstanding = after.get_mut(outcome)?;
+    *outstanding = outstanding.checked_sub(amount)?;
+    let change = cost(shares, liquidity)?.saturating_sub(cost(&after, liquidity)?);
+    Some(change.checked_mul_int(liquidity)?.saturating_sub(1))
+}
+
+/// Returns the liquidity of a market maker over a number of outcomes that loses at most the
+/// subsidy, `subsidy / ln(outcomes)`.
+pub(crate) fn liquidity(subsidy: u128, outcomes: u32) -> u128 {
+    let ln_outcomes = ln(FixedI128::saturating_from_integer(outcomes));
+    multiply_by_rational_with_rounding(
+        subsidy,
+        FixedI128::accuracy() as u128,
+        ln_outcomes.into_inner() as u128,
+        Rounding::Down,
+    )
+    .unwrap_or_default()
+}
@@ -0,0 +1,772 @@
+//! Storage migrations for pallet-betting.
+
+use super::*;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::marker::PhantomData;
+
+/// Keys the matches by `MatchId` instead of the account of their creator, moves their bets out
+/// of the `Match` struct and into the `Bets` map, and their stakes out of the pallet account and
+/// into the pot account of every match. Every match is queued to be finalised by `on_idle`.
+pub mod v1 {
+    use super::*;
+    use frame_support::{
+        log,
+        sp_runtime::traits::AccountIdConversion,
+        storage_alias,
+        traits::{BalanceStatus, ReservableCurrency},
+    };
+
+    /// A bet as stored in the `bets` vector of a match before version 1.
+    #[derive(Encode, Decode)]
+    pub struct OldBet<AccountId, Balance> {
+        pub bettor: AccountId,
+        pub amount: Balance,
+        pub result: MatchResult,
+    }
+
+    /// A match as stored before version 1, keyed by the account of its creator.
+    #[derive(Encode, Decode)]
+    pub struct OldMatch<AccountId, BlockNumber, TeamName, Balance> {
+        pub start: BlockNumber,
+        pub length: BlockNumber,
+        pub team1: TeamName,
+        pub team2: TeamName,
+        pub result: Option<MatchResult>,
+        pub bets: Vec<OldBet<AccountId, Balance>>,
+        pub deposit: Balance,
+    }
+
+    pub type OldMatchOf<T> = OldMatch<
+        AccountIdOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+        TeamName<T>,
+        BalanceOf<T>,
+    >;
+
+    #[storage_alias]
+    pub type Matches<T: Config> =
+        StorageMap<Pallet<T>, Twox64Concat, AccountIdOf<T>, OldMatchOf<T>>;
+
+    #[storage_alias]
+    pub type MatchHashes<T: Config> =
+        StorageMap<Pallet<T>, Twox64Concat, <T as frame_system::Config>::Hash, AccountIdOf<T>>;
+
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != 0 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut reads: u64 = 2;
+            let mut writes: u64 = 2;
+
+            // Every match is drained before being inserted again under its id
+            let old_matches: Vec<_> = Matches::<T>::drain().collect();
+            let _ = MatchHashes::<T>::clear(u32::MAX, None);
+            // The stakes of every match were held by the pallet account
+            let old_pot: AccountIdOf<T> = T::PalletId::get().into_account_truncating();
+
+            let mut match_id = <NextMatchId<T>>::get();
+            let mut unfunded: u32 = 0;
+            for (creator, old) in old_matches {
+                reads += 3;
+                writes += 8;
+                let mut betting_match = v2::OldMatch {
+                    creator: creator.clone(),
+                    start: old.start,
+                    length: old.length,
+                    team1: old.team1,
+                    team2: old.team2,
+                    result: old.result,
+                    dispute_end: None,
+                    asset: None,
+                    bet_count: 0,
+                    totals: Default::default(),
+                    rollover: Zero::zero(),
+                    commission: Permill::zero(),
+                    settled_pot: None,
+                    claimed_stakes: Zero::zero(),
+                    paid_winnings: Zero::zero(),
+                    deposit: old.deposit,
+                };
+
+                // The same bettor could hold several bets on a result, they are merged
+                let mut stakes: BalanceOf<T> = Zero::zero();
+                for old_bet in old.bets {
+                    reads += 1;
+                    writes += 1;
+                    v2::Bets::<T>::mutate(
+                        (match_id, &old_bet.bettor, old_bet.result),
+                        |maybe_bet| match maybe_bet {
+                            Some(bet) => bet.amount += old_bet.amount,
+                            None => {
+                                *maybe_bet = Some(Bet {
+                                    amount: old_bet.amount,
+                                    claimed: false,
+                                });
+                                betting_match.bet_count += 1;
+                            }
+                        },
+                    );
+                    *betting_match.totals.get_mut(old_bet.result) += old_bet.amount;
+                    stakes += old_bet.amount;
+                }
+
+                // The pot is seeded with the existential deposit out of the deposit of the
+                // creator, which gets it back with the pot once the match is closed
+                let pot = Pallet::<T>::pot_account(match_id);
+                let seed = T::Currency::minimum_balance().min(betting_match.deposit);
+                let unmoved =
+                    T::Currency::repatriate_reserved(&creator, &pot, seed, BalanceStatus::Free)
+                        .unwrap_or(seed);
+                betting_match.deposit -= seed - unmoved;
+                if T::Currency::transfer(&old_pot, &pot, stakes, AllowDeath).is_err() {
+                    log::warn!(
+                        target: "runtime::betting",
+                        "the stakes of match {} could not be moved to its pot",
+                        match_id
+                    );
+                    unfunded += 1;
+                }
+
+                // The match is finalised by `on_idle` once its result is final, or refunded
+                // once it can no longer get one
+                let finalise_at = match betting_match.result {
+                    Some(_) => frame_system::Pallet::<T>::block_number(),
+                    None => betting_match.start + betting_match.length + T::ResultTimeout::get(),
+                };
+                Pallet::<T>::enqueue(finalise_at, match_id);
+
+                <crate::MatchHashes<T>>::insert(v2::old_match_hash::<T>(&betting_match), match_id);
+                <MatchesByCreator<T>>::insert(&creator, match_id, ());
+                v2::Matches::<T>::insert(match_id, betting_match);
+                match_id += 1;
+            }
+            <NextMatchId<T>>::put(match_id);
+            if unfunded > 0 {
+                log::warn!(
+                    target: "runtime::betting",
+                    "{} migrated matches have no stakes in their pot",
+                    unfunded
+                );
+            }
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            Ok((Matches::<T>::iter_keys().count() as u32).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure!(
+                StorageVersion::get::<Pallet<T>>() == 1,
+                "the storage version must be 1 after the migration"
+            );
+            let old_count = u32::decode(&mut state.as_slice())
+                .map_err(|_| "the number of matches must be decoded")?;
+            ensure!(
+                v2::Matches::<T>::iter_keys().count() as u32 == old_count,
+                "every match must be migrated"
+            );
+            let old_pot: AccountIdOf<T> = T::PalletId::get().into_account_truncating();
+            ensure!(
+                T::Currency::free_balance(&old_pot).is_zero(),
+                "the stakes must be moved to the pots of the matches"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Replaces the two teams of every match with a list of outcomes and keys the bets by the index
+/// of their outcome. The upgrade panics unless `MaxOutcomes` holds both teams and the draw, and
+/// `MaxTeamNameLength` holds the name of the draw.
+pub mod v2 {
+    use super::*;
+    use frame_support::storage_alias;
+
+    /// Amount bet on every result of a match before version 2.
+    #[derive(Encode, Decode, Default)]
+    pub struct ResultTotals<Balance> {
+        pub team1_victory: Balance,
+        pub team2_victory: Balance,
+        pub draw: Balance,
+    }
+
+    impl<Balance: Copy> ResultTotals<Balance> {
+        /// Returns the amount bet on a result.
+        pub fn get(&self, result: MatchResult) -> Balance {
+            match result {
+                MatchResult::Team1Victory => self.team1_victory,
+                MatchResult::Team2Victory => self.team2_victory,
+                MatchResult::Draw => self.draw,
+            }
+        }
+
+        pub fn get_mut(&mut self, result: MatchResult) -> &mut Balance {
+            match result {
+                MatchResult::Team1Victory => &mut self.team1_victory,
+                MatchResult::Team2Victory => &mut self.team2_victory,
+                MatchResult::Draw => &mut self.draw,
+            }
+        }
+    }
+
+    /// A match as stored in version 1.
+    #[derive(Encode, Decode)]
+    pub struct OldMatch<AccountId, BlockNumber, TeamName, Balance, AssetId> {
+        pub creator: AccountId,
+        pub start: BlockNumber,
+        pub length: BlockNumber,
+        pub team1: TeamName,
+        pub team2: TeamName,
+        pub result: Option<MatchResult>,
+        pub dispute_end: Option<BlockNumber>,
+        pub asset: Option<AssetId>,
+        pub bet_count: u32,
+        pub totals: ResultTotals<Balance>,
+        pub rollover: Balance,
+        pub commission: Permill,
+        pub settled_pot: Option<Balance>,
+        pub claimed_stakes: Balance,
+        pub paid_winnings: Balance,
+        pub deposit: Balance,
+    }
+
+    pub type OldMatchOf<T> = OldMatch<
+        AccountIdOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+        TeamName<T>,
+        BalanceOf<T>,
+        AssetIdOf<T>,
+    >;
+
+    #[storage_alias]
+    pub type Matches<T: Config> = StorageMap<Pallet<T>, Twox64Concat, MatchId, OldMatchOf<T>>;
+
+    #[storage_alias]
+    pub type Bets<T: Config> = StorageNMap<
+        Pallet<T>,
+        (
+            NMapKey<Twox64Concat, MatchId>,
+            NMapKey<Twox64Concat, AccountIdOf<T>>,
+            NMapKey<Twox64Concat, MatchResult>,
+        ),
+        Bet<BalanceOf<T>>,
+    >;
+
+    /// Returns the hash a match had in `MatchHashes` before version 2.
+    pub(super) fn old_match_hash<T: Config>(old: &OldMatchOf<T>) -> T::Hash {
+        let entropy = (&old.team1, &old.team2, old.start, old.length).using_encoded(blake2_256);
+        Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+            .expect("infinite length input; no invalid inputs for type; qed")
+    }
+
+    pub struct MigrateToV2<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != 1 {
+                return T::DbWeight::get().reads(1);
+            }
+            // The upgrade is aborted rather than dropping or renaming the matches that do not fit
+            assert!(
+                T::MaxOutcomes::get() >= 3,
+                "`MaxOutcomes` must hold both teams and the draw of every match"
+            );
+            assert!(
+                T::MaxTeamNameLength::get() as usize >= DRAW.len(),
+                "`MaxTeamNameLength` must hold the name of the draw"
+            );
+
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 1;
+
+            // Key the bets by outcome index, they are all drained before being inserted again
+            let old_bets: Vec<_> = Bets::<T>::drain().collect();
+            for ((match_id, bettor, result), bet) in old_bets {
+                reads += 1;
+                writes += 2;
+                <crate::Bets<T>>::insert((match_id, bettor, result.index()), bet);
+            }
+
+            v3::Matches::<T>::translate::<OldMatchOf<T>, _>(|match_id, old| {
+                reads += 1;
+                writes += 3;
+                let old_hash = old_match_hash::<T>(&old);
+                let draw: TeamName<T> = DRAW
+                    .to_vec()
+                    .try_into()
+                    .expect("`MaxTeamNameLength` is checked to hold the draw; qed");
+                let outcomes = vec![
+                    Outcome {
+                        name: old.team1,
+                        total: old.totals.team1_victory,
+                    },
+                    Outcome {
+                        name: old.team2,
+                        total: old.totals.team2_victory,
+                    },
+                    Outcome {
+                        name: draw,
+                        total: old.totals.draw,
+                    },
+                ]
+                .try_into()
+                .expect("`MaxOutcomes` is checked to hold three outcomes; qed");
+                let betting_match = v3::OldMatch {
+                    creator: old.creator,
+                    start: old.start,
+                    length: old.length,
+                    outcomes,
+                    result: old.result.map(MatchResult::index),
+                    dispute_end: old.dispute_end,
+                    asset: old.asset,
+                    bet_count: old.bet_count,
+                    rollover: old.rollover,
+                    commission: old.commission,
+                    settled_pot: old.settled_pot,
+                    claimed_stakes: old.claimed_stakes,
+                    paid_winnings: old.paid_winnings,
+                    deposit: old.deposit,
+                };
+
+                // The hash of a match now covers the names of all its outcomes
+                <MatchHashes<T>>::remove(old_hash);
+                <MatchHashes<T>>::insert(v3::old_match_hash::<T>(&betting_match), match_id);
+
+                Some(betting_match)
+            });
+
+            StorageVersion::new(2).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            ensure!(
+                T::MaxOutcomes::get() >= 3,
+                "`MaxOutcomes` must hold both teams and the draw of every match"
+            );
+            ensure!(
+                T::MaxTeamNameLength::get() as usize >= DRAW.len(),
+                "`MaxTeamNameLength` must hold the name of the draw"
+            );
+            Ok((Matches::<T>::iter_keys().count() as u32).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure!(
+                StorageVersion::get::<Pallet<T>>() == 2,
+                "the storage version must be 2 after the migration"
+            );
+            let old_count = u32::decode(&mut state.as_slice())
+                .map_err(|_| "the number of matches must be decoded")?;
+            ensure!(
+                v3::Matches::<T>::iter_keys().count() as u32 == old_count,
+                "every match must be migrated"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Adds the score market, the derived markets and the score to every match.
+pub mod v3 {
+    use super::*;
+    use frame_support::storage_alias;
+
+    /// A match as stored in version 2.
+    #[derive(Encode, Decode)]
+    pub struct OldMatch<AccountId, BlockNumber, Outcomes, Balance, AssetId> {
+        pub creator: AccountId,
+        pub start: BlockNumber,
+        pub length: BlockNumber,
+        pub outcomes: Outcomes,
+        pub result: Option<OutcomeIndex>,
+        pub dispute_end: Option<BlockNumber>,
+        pub asset: Option<AssetId>,
+        pub bet_count: u32,
+        pub rollover: Balance,
+        pub commission: Permill,
+        pub settled_pot: Option<Balance>,
+        pub claimed_stakes: Balance,
+        pub paid_winnings: Balance,
+        pub deposit: Balance,
+    }
+
+    pub type OldMatchOf<T> = OldMatch<
+        AccountIdOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+        OutcomesOf<T>,
+        BalanceOf<T>,
+        AssetIdOf<T>,
+    >;
+
+    #[storage_alias]
+    pub type Matches<T: Config> = StorageMap<Pallet<T>, Twox64Concat, MatchId, OldMatchOf<T>>;
+
+    /// Returns the hash of a match in `MatchHashes` in version 2, which is still the hash of
+    /// the matches that are not derived from another.
+    pub(super) fn old_match_hash<T: Config>(old: &OldMatchOf<T>) -> T::Hash {
+        let names: Vec<_> = old.outcomes.iter().map(|outcome| &outcome.name).collect();
+        let entropy = (names, old.start, old.length).using_encoded(blake2_256);
+        Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+            .expect("infinite length input; no invalid inputs for type; qed")
+    }
+
+    pub struct MigrateToV3<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != 2 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 1;
+            v4::Matches::<T>::translate::<OldMatchOf<T>, _>(|_, old| {
+                reads += 1;
+                writes += 1;
+                // The two-team matches can now be settled from their score
+                let is_two_team = old.outcomes.len() == 3
+                    && old.outcomes[MatchResult::Draw.index() as usize]
+                        .name
+                        .as_slice()
+                        == DRAW;
+                Some(v4::OldMatch {
+                    creator: old.creator,
+                    start: old.start,
+                    length: old.length,
+                    outcomes: old.outcomes,
+                    market: is_two_team.then_some(ScoreMarket::MatchResult),
+                    parent: None,
+                    score: None,
+                    result: old.result,
+                    dispute_end: old.dispute_end,
+                    asset: old.asset,
+                    bet_count: old.bet_count,
+                    rollover: old.rollover,
+                    commission: old.commission,
+                    settled_pot: old.settled_pot,
+                    claimed_stakes: old.claimed_stakes,
+                    paid_winnings: old.paid_winnings,
+                    deposit: old.deposit,
+                })
+            });
+
+            StorageVersion::new(3).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            ensure!(
+                StorageVersion::get::<Pallet<T>>() == 3,
+                "the storage version must be 3 after the migration"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Adds whether every match has been voided.
+pub mod v4 {
+    use super::*;
+    use frame_support::storage_alias;
+
+    /// A match as stored in version 3.
+    #[derive(Encode, Decode)]
+    pub struct OldMatch<AccountId, BlockNumber, Outcomes, Balance, AssetId> {
+        pub creator: AccountId,
+        pub start: BlockNumber,
+        pub length: BlockNumber,
+        pub outcomes: Outcomes,
+        pub market: Option<ScoreMarket>,
+        pub parent: Option<MatchId>,
+        pub score: Option<Score>,
+        pub result: Option<OutcomeIndex>,
+        pub dispute_end: Option<BlockNumber>,
+        pub asset: Option<AssetId>,
+        pub bet_count: u32,
+        pub rollover: Balance,
+        pub commission: Permill,
+        pub settled_pot: Option<Balance>,
+        pub claimed_stakes: Balance,
+        pub paid_winnings: Balance,
+        pub deposit: Balance,
+    }
+
+    pub type OldMatchOf<T> = OldMatch<
+        AccountIdOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+        OutcomesOf<T>,
+        BalanceOf<T>,
+        AssetIdOf<T>,
+    >;
+
+    #[storage_alias]
+    pub type Matches<T: Config> = StorageMap<Pallet<T>, Twox64Concat, MatchId, OldMatchOf<T>>;
+
+    pub struct MigrateToV4<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != 3 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 1;
+            v5::Matches::<T>::translate::<OldMatchOf<T>, _>(|_, old| {
+                reads += 1;
+                writes += 1;
+                Some(v5::OldMatch {
+                    creator: old.creator,
+                    start: old.start,
+                    length: old.length,
+                    outcomes: old.outcomes,
+                    market: old.market,
+                    parent: old.parent,
+                    score: old.score,
+                    voided: false,
+                    result: old.result,
+                    dispute_end: old.dispute_end,
+                    asset: old.asset,
+                    bet_count: old.bet_count,
+                    rollover: old.rollover,
+                    commission: old.commission,
+                    settled_pot: old.settled_pot,
+                    claimed_stakes: old.claimed_stakes,
+                    paid_winnings: old.paid_winnings,
+                    deposit: old.deposit,
+                })
+            });
+
+            StorageVersion::new(4).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            ensure!(
+                StorageVersion::get::<Pallet<T>>() == 4,
+                "the storage version must be 4 after the migration"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Adds the registered teams of every match, and registers the teams of the tournaments in
+/// progress. The upgrade panics if the team ids left can not hold them all.
+pub mod v5 {
+    use super::*;
+    use frame_support::storage_alias;
+
+    /// A match as stored in version 4.
+    #[derive(Encode, Decode)]
+    pub struct OldMatch<AccountId, BlockNumber, Outcomes, Balance, AssetId> {
+        pub creator: AccountId,
+        pub start: BlockNumber,
+        pub length: BlockNumber,
+        pub outcomes: Outcomes,
+        pub market: Option<ScoreMarket>,
+        pub parent: Option<MatchId>,
+        pub score: Option<Score>,
+        pub voided: bool,
+        pub result: Option<OutcomeIndex>,
+        pub dispute_end: Option<BlockNumber>,
+        pub asset: Option<AssetId>,
+        pub bet_count: u32,
+        pub rollover: Balance,
+        pub commission: Permill,
+        pub settled_pot: Option<Balance>,
+        pub claimed_stakes: Balance,
+        pub paid_winnings: Balance,
+        pub deposit: Balance,
+    }
+
+    pub type OldMatchOf<T> = OldMatch<
+        AccountIdOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+        OutcomesOf<T>,
+        BalanceOf<T>,
+        AssetIdOf<T>,
+    >;
+
+    #[storage_alias]
+    pub type Matches<T: Config> = StorageMap<Pallet<T>, Twox64Concat, MatchId, OldMatchOf<T>>;
+
+    /// A tournament as stored in version 4, with the names of its teams.
+    pub type OldTournamentOf<T> = Tournament<
+        AccountIdOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+        OutcomeNames<T>,
+        BoundedVec<Fixture, <T as Config>::MaxTournamentMatches>,
+    >;
+
+    #[storage_alias]
+    pub type Tournaments<T: Config> =
+        StorageMap<Pallet<T>, Twox64Concat, TournamentId, OldTournamentOf<T>>;
+
+    /// Returns the ids of the teams of a tournament, registering the names that are not. A blank
+    /// name, or one looked up as another team of the tournament, is registered as a new team
+    /// with a number after it, such as `Team 1` or `Lions 2`.
+    fn team_ids<T: Config>(names: OutcomeNames<T>) -> Vec<TeamId> {
+        let mut team_ids = Vec::new();
+        for name in names {
+            let team_id = match Pallet::<T>::team_key(&name) {
+                Ok(key) => match <crate::TeamIds<T>>::get(key) {
+                    Some(team_id) if team_ids.contains(&team_id) => numbered_team::<T>(&name, 2),
+                    Some(team_id) => team_id,
+                    None => register::<T>(name),
+                },
+                Err(_) => numbered_team::<T>(b"Team", 1),
+            };
+            team_ids.push(team_id);
+        }
+        team_ids
+    }
+
+    /// Registers a team named after a base name and the first number from `first` that makes
+    /// its name unique, cutting the base name to fit.
+    fn numbered_team<T: Config>(base: &[u8], first: u32) -> TeamId {
+        let max_length = T::MaxTeamNameLength::get() as usize;
+        let mut number = first;
+        loop {
+            let mut suffix = b" ".to_vec();
+            suffix.extend(digits(number));
+            let mut name = base[..base.len().min(max_length.saturating_sub(suffix.len()))].to_vec();
+            name.extend(suffix);
+            let is_unique = Pallet::<T>::team_key(&name)
+                .map_or(false, |key| !<crate::TeamIds<T>>::contains_key(key));
+            if let (true, Ok(name)) = (is_unique, TeamName::<T>::try_from(name)) {
+                return register::<T>(name);
+            }
+            number += 1;
+        }
+    }
+
+    /// Registers a team whose name is not registered yet.
+    fn register<T: Config>(name: TeamName<T>) -> TeamId {
+        Pallet::<T>::insert_team(Team {
+            name,
+            metadata: None,
+        })
+        .expect("the name is not registered and the team ids left are checked; qed")
+    }
+
+    pub struct MigrateToV5<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != 4 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            // The upgrade is aborted rather than dropping the tournaments whose teams can not
+            // all be registered
+            let mut reads: u64 = 2;
+            let names: u64 = Tournaments::<T>::iter_values()
+                .map(|tournament| {
+                    reads += 1;
+                    tournament.teams.len() as u64
+                })
+                .sum();
+            assert!(
+                u64::from(<crate::NextTeamId<T>>::get()) + names <= u64::from(TeamId::MAX),
+                "the team ids left must hold the teams of every tournament"
+            );
+
+            let mut writes: u64 = 1;
+            // The matches created so far keep their hash, which does not depend on teams
+            <crate::Matches<T>>::translate::<OldMatchOf<T>, _>(|_, old| {
+                reads += 1;
+                writes += 1;
+                Some(Match {
+                    creator: old.creator,
+                    start: old.start,
+                    length: old.length,
+                    teams: None,
+                    outcomes: old.outcomes,
+                    market: old.market,
+                    parent: old.parent,
+                    score: old.score,
+                    voided: old.voided,
+                    result: old.result,
+                    dispute_end: old.dispute_end,
+                    asset: old.asset,
+                    bet_count: old.bet_count,
+                    rollover: old.rollover,
+                    commission: old.commission,
+                    settled_pot: old.settled_pot,
+                    claimed_stakes: old.claimed_stakes,
+                    paid_winnings: old.paid_winnings,
+                    deposit: old.deposit,
+                })
+            });
+
+            // The next rounds of a tournament are scheduled between registered teams
+            <crate::Tournaments<T>>::translate::<OldTournamentOf<T>, _>(|_, old| {
+                reads += 1 + old.teams.len() as u64;
+                writes += 1 + 3 * old.teams.len() as u64;
+                let teams = team_ids::<T>(old.teams)
+                    .try_into()
+                    .expect("a tournament has as many teams as names; qed");
+                Some(Tournament {
+                    organizer: old.organizer,
+                    format: old.format,
+                    teams,
+                    start: old.start,
+                    round_length: old.round_length,
+                    match_length: old.match_length,
+                    commission: old.commission,
+                    rounds: old.rounds,
+                    fixtures: old.fixtures,
+                    outright: old.outright,
+                })
+            });
+
+            StorageVersion::new(5).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            let names: u64 = Tournaments::<T>::iter_values()
+                .map(|tournament| tournament.teams.len() as u64)
+                .sum();
+            ensure!(
+                u64::from(<crate::NextTeamId<T>>::get()) + names <= u64::from(TeamId::MAX),
+                "the team ids left must hold the teams of every tournament"
+            );
+            Ok((Tournaments::<T>::iter_keys().count() as u32).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            ensure!(
+                StorageVersion::get::<Pallet<T>>() == 5,
+                "the storage version must be 5 after the migration"
+            );
+            let old_count = u32::decode(&mut state.as_slice())
+                .map_err(|_| "the number of tournaments must be decoded")?;
+            ensure!(
+                <crate::Tournaments<T>>::iter_keys().count() as u32 == old_count,
+                "every tournament must be migrated"
+            );
+            Ok(())
+        }
+    }
+}
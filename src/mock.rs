@@ -1,11 +1,14 @@
 use crate as pallet_betting;
 use frame_support::traits::{ConstU128, ConstU16, ConstU32, ConstU64, GenesisBuild};
-use frame_support::{parameter_types, PalletId};
+use frame_support::traits::{EitherOfDiverse, EqualPrivilegeOnly};
+use frame_support::{ord_parameter_types, parameter_types, weights::Weight, PalletId};
 use frame_system as system;
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    Permill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -21,6 +24,9 @@ frame_support::construct_runtime!(
         System: frame_system,
         Betting: pallet_betting,
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Preimage: pallet_preimage::{Pallet, Call, Storage, Event<T>},
+        Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
     }
 
 );
@@ -37,6 +43,49 @@ impl pallet_balances::Config for Test {
     type ReserveIdentifier = [u8; 8];
 }
 
+impl pallet_assets::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = u128;
+    type AssetId = u32;
+    type Currency = Balances;
+    type ForceOrigin = EnsureRoot<u64>;
+    type AssetDeposit = ConstU128<1>;
+    type AssetAccountDeposit = ConstU128<1>;
+    type MetadataDepositBase = ConstU128<1>;
+    type MetadataDepositPerByte = ConstU128<1>;
+    type ApprovalDeposit = ConstU128<1>;
+    type StringLimit = ConstU32<50>;
+    type Freezer = ();
+    type Extra = ();
+    type WeightInfo = ();
+}
+
+impl pallet_preimage::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type Currency = Balances;
+    type ManagerOrigin = EnsureRoot<u64>;
+    type BaseDeposit = ConstU128<1>;
+    type ByteDeposit = ConstU128<1>;
+}
+
+parameter_types! {
+    pub MaximumSchedulerWeight: Weight = Weight::from_ref_time(2_000_000_000_000);
+}
+
+impl pallet_scheduler::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeOrigin = RuntimeOrigin;
+    type PalletsOrigin = OriginCaller;
+    type RuntimeCall = RuntimeCall;
+    type MaximumWeight = MaximumSchedulerWeight;
+    type ScheduleOrigin = EnsureRoot<u64>;
+    type MaxScheduledPerBlock = ConstU32<10>;
+    type WeightInfo = ();
+    type OriginPrivilegeCmp = EqualPrivilegeOnly;
+    type Preimages = Preimage;
+}
+
 impl system::Config for Test {
     type BaseCallFilter = frame_support::traits::Everything;
     type BlockWeights = ();
@@ -67,15 +116,53 @@ impl system::Config for Test {
 parameter_types! {
     pub const MatchDeposit: u64 = 10;
     pub const BettingPalletId: PalletId = PalletId(*b"bet_mock");
+    pub static NoWinner: pallet_betting::NoWinnerPolicy = pallet_betting::NoWinnerPolicy::Refund;
+    pub static PlatformFee: Permill = Permill::zero();
+    pub const MaxCreatorCommission: Permill = Permill::from_percent(10);
+    pub static CancellationFee: Permill = Permill::zero();
+    pub const AssetTreasury: u64 = TREASURY;
+}
+
+ord_parameter_types! {
+    pub const Oracle: u64 = ORACLE;
 }
 
 impl pallet_betting::Config for Test {
     type PalletId = BettingPalletId;
     type Currency = Balances;
+    type Assets = Assets;
     type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Scheduler = Scheduler;
+    type Preimages = Preimage;
+    type SettlementRetryPeriod = ConstU64<5>;
     type MaxTeamNameLength = ConstU32<64>;
+    type MaxTeamMetadataLength = ConstU32<64>;
+    type MaxOutcomes = ConstU32<8>;
     type MaxBetsPerMatch = ConstU32<3>;
+    type MaxDerivedMarkets = ConstU32<4>;
     type MatchDeposit = MatchDeposit;
+    type CancelOrigin = EnsureRoot<u64>;
+    type TeamOrigin = EnsureRoot<u64>;
+    type ResultOrigin = EitherOfDiverse<EnsureRoot<u64>, EnsureSignedBy<Oracle, u64>>;
+    type MaxSuspension = ConstU64<3>;
+    type MaxParlayLegs = ConstU32<4>;
+    type MaxParlaysPerMatch = ConstU32<3>;
+    type MaxTournamentMatches = ConstU32<16>;
+    type DisputePeriod = ConstU64<5>;
+    type DisputeBond = ConstU128<50>;
+    type MaxDisputes = ConstU32<3>;
+    type DisputeResolutionOrigin = EnsureRoot<u64>;
+    type Slashed = ();
+    type ClaimPeriod = ConstU64<20>;
+    type ResultTimeout = ConstU64<30>;
+    type TimeoutIncentive = ConstU128<4>;
+    type NoWinnerPolicy = NoWinner;
+    type Treasury = ();
+    type PlatformFee = PlatformFee;
+    type MaxCreatorCommission = MaxCreatorCommission;
+    type CancellationFee = CancellationFee;
+    type AssetTreasury = AssetTreasury;
     type WeightInfo = ();
 }
 
@@ -84,6 +171,9 @@ pub(crate) const ACCOUNT_B: u64 = 1;
 pub(crate) const ACCOUNT_C: u64 = 2;
 pub(crate) const ACCOUNT_D: u64 = 3;
 pub(crate) const ACCOUNT_E: u64 = 4;
+pub(crate) const ORACLE: u64 = 9;
+pub(crate) const TREASURY: u64 = 8;
+pub(crate) const ASSET: u32 = 1;
 pub(crate) const INIT_BALANCE: u128 = 1_000_000_000_000_000;
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -103,13 +193,19 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage(&mut storage)
     .unwrap();
 
-    GenesisBuild::<Test>::assimilate_storage(
-		&pallet_betting::GenesisConfig {
-			existential_deposit: 2_000_000_000_000_000,
-		},
-		&mut storage,
-	)
-	.unwrap();
+    pallet_assets::GenesisConfig::<Test> {
+        assets: vec![(ASSET, ACCOUNT_A, true, 1)],
+        metadata: vec![],
+        accounts: vec![
+            (ASSET, ACCOUNT_A, INIT_BALANCE),
+            (ASSET, ACCOUNT_B, INIT_BALANCE),
+            (ASSET, ACCOUNT_C, INIT_BALANCE),
+            (ASSET, ACCOUNT_D, INIT_BALANCE),
+            (ASSET, ACCOUNT_E, INIT_BALANCE),
+        ],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
 
     let mut test_ext: sp_io::TestExternalities = storage.into();
     test_ext.execute_with(|| System::set_block_number(1));
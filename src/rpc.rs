@@ -1,4 +1,4 @@
-use crate::{Bets, Config, Error, Match, Pallet, TeamName, BalanceOf};
+use crate::{BalanceOf, Config, Error, MatchId, MatchOf, OutcomeIndex, Pallet};
 use codec::{Decode, Encode};
 use scale_info::prelude::format;
 use sp_std::fmt::Debug;
@@ -22,9 +22,29 @@ impl<T: Config> From<Error<T>> for RpcError {
 }
 
 impl<T: Config> Pallet<T> {
-    pub fn get_match(
-        match_id: T::AccountId,
-    ) -> RpcResult<Match<T::BlockNumber, TeamName<T>, Bets<T>, BalanceOf<T>>> {
+    pub fn get_match(match_id: MatchId) -> RpcResult<MatchOf<T>> {
         Self::get_matches(match_id).ok_or(RpcError::MatchDoesNotExist)
     }
+
+    pub fn get_positions(
+        match_id: MatchId,
+        who: T::AccountId,
+    ) -> RpcResult<Vec<(OutcomeIndex, BalanceOf<T>)>> {
+        Self::get_matches(match_id).ok_or(RpcError::MatchDoesNotExist)?;
+        Ok(Self::positions(match_id, &who))
+    }
+
+    pub fn quote_shares(
+        match_id: MatchId,
+        outcome: OutcomeIndex,
+        shares: BalanceOf<T>,
+    ) -> RpcResult<BalanceOf<T>> {
+        let betting_match = Self::get_matches(match_id).ok_or(RpcError::MatchDoesNotExist)?;
+        let maker = Self::get_market_maker(match_id).ok_or(Error::<T>::NoMarketMaker)?;
+        if !betting_match.has_outcome(outcome) {
+            return Err(Error::<T>::UnknownOutcome.into());
+        }
+        Self::buy_cost(&betting_match, &maker, outcome, shares)
+            .ok_or_else(|| Error::<T>::SlippageExceeded.into())
+    }
 }
@@ -1,21 +1,169 @@
 use super::*;
 use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
-use frame_system::pallet_prelude::*;
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Hooks, OnRuntimeUpgrade, StorageVersion},
+    weights::Weight,
+};
 use frame_system::RawOrigin;
 use pallet_balances::Error as BalancesError;
 use sp_runtime::traits::BadOrigin;
 
-fn create_match(who: u64, t1: &str, t2: &str, start: u64, length: u64) -> AccountIdOf<Test> {
+/// Returns the id of a team, registering it the first time.
+fn team(name: &str) -> TeamId {
+    let key = Betting::team_key(name.as_bytes()).unwrap();
+    Betting::get_team_id(key).unwrap_or_else(|| {
+        let team_id = Betting::next_team_id();
+        assert_ok!(Betting::register_team(
+            RawOrigin::Root.into(),
+            name.as_bytes().to_vec(),
+            None
+        ));
+        team_id
+    })
+}
+
+fn create_match(who: u64, t1: &str, t2: &str, start: u64, length: u64) -> MatchId {
+    let match_id = Betting::next_match_id();
     // Dispatch a signed extrinsic.
     assert_ok!(Betting::create_match_to_bet(
         RuntimeOrigin::signed(who.clone()),
-        t1.as_bytes().to_vec(),
-        t2.as_bytes().to_vec(),
+        team(t1),
+        team(t2),
         start,
-        length
+        length,
+        Permill::zero(),
+        None
+    ));
+    match_id
+}
+
+/// Returns the names of the outcomes of a race between a number of horses.
+fn horses(count: u32) -> Vec<Vec<u8>> {
+    (1..=count)
+        .map(|horse| format!("horse{}", horse).into_bytes())
+        .collect()
+}
+
+fn create_fixed_odds_market(who: u64, odds: Vec<FixedU128>, bond: u128) -> MatchId {
+    let match_id = Betting::next_match_id();
+    assert_ok!(Betting::create_fixed_odds_market(
+        RuntimeOrigin::signed(who),
+        horses(odds.len() as u32),
+        odds,
+        bond,
+        10,
+        10
+    ));
+    match_id
+}
+
+fn odds(odds: &[u32]) -> Vec<FixedU128> {
+    odds.iter()
+        .map(|odds| FixedU128::saturating_from_integer(*odds))
+        .collect()
+}
+
+fn create_amm_market(who: u64, outcomes: u32, subsidy: u128) -> MatchId {
+    let match_id = Betting::next_match_id();
+    assert_ok!(Betting::create_amm_market(
+        RuntimeOrigin::signed(who),
+        horses(outcomes),
+        subsidy,
+        10,
+        10
+    ));
+    match_id
+}
+
+fn derive_market(who: u64, match_id: MatchId, market: ScoreMarket) -> MatchId {
+    let market_id = Betting::next_match_id();
+    assert_ok!(Betting::create_derived_market(
+        RuntimeOrigin::signed(who),
+        match_id,
+        market,
+        Permill::zero()
+    ));
+    market_id
+}
+
+/// Creates a quarter handicap market won by half by team 2, where everybody bet on team 1.
+fn create_half_win_without_winners() -> MatchId {
+    let match_id = create_match(1, "team1", "team2", 10, 10);
+    let handicap_id = derive_market(1, match_id, ScoreMarket::Handicap(-1));
+    assert_ok!(Betting::bet(RuntimeOrigin::signed(2), handicap_id, 41, 0));
+    assert_ok!(Betting::bet(RuntimeOrigin::signed(3), handicap_id, 21, 0));
+
+    System::set_block_number(22);
+    assert_ok!(Betting::set_score(
+        RawOrigin::Root.into(),
+        match_id,
+        Score { team1: 1, team2: 1 }
+    ));
+    System::set_block_number(27);
+    handicap_id
+}
+
+fn outcome_names(match_id: MatchId) -> Vec<Vec<u8>> {
+    Betting::get_matches(match_id)
+        .unwrap()
+        .outcomes
+        .iter()
+        .map(|outcome| outcome.name.to_vec())
+        .collect()
+}
+
+fn create_tournament(format: TournamentFormat, teams: &[&str]) -> TournamentId {
+    let tournament_id = Betting::next_tournament_id();
+    assert_ok!(Betting::create_tournament(
+        RuntimeOrigin::signed(1),
+        format,
+        teams.iter().map(|name| team(name)).collect(),
+        10,
+        20,
+        10,
+        Permill::zero()
+    ));
+    tournament_id
+}
+
+fn fixtures(tournament_id: TournamentId) -> Vec<(MatchId, u32, u32)> {
+    Betting::get_tournament(tournament_id)
+        .unwrap()
+        .fixtures
+        .iter()
+        .map(|fixture| (fixture.match_id, fixture.home, fixture.away))
+        .collect()
+}
+
+fn create_match_with_result(result: OutcomeIndex) -> MatchId {
+    let match_id = create_match(1, "team1", "team2", 10, 10);
+    assert_ok!(Betting::bet(
+        RuntimeOrigin::signed(2),
+        match_id,
+        10,
+        MatchResult::Team1Victory.index()
+    ));
+    assert_ok!(Betting::bet(
+        RuntimeOrigin::signed(3),
+        match_id,
+        10,
+        MatchResult::Team2Victory.index()
     ));
-    who.into()
+    System::set_block_number(22);
+    assert_ok!(Betting::set_result(
+        RawOrigin::Root.into(),
+        match_id,
+        result
+    ));
+    match_id
+}
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Scheduler::on_initialize(System::block_number());
+    }
 }
 
 #[test]
@@ -24,10 +172,13 @@ fn creates_a_match() {
         let match_id = create_match(1, "team1", "team2", 10, 10);
         // Read pallet storage and assert an expected result.
         let stored_bet = Betting::get_matches(match_id).unwrap();
+        assert_eq!(stored_bet.creator, 1);
         assert_eq!(stored_bet.start, 10);
         assert_eq!(stored_bet.length, 10);
-        assert_eq!(stored_bet.team1.to_owned(), "team1".as_bytes().to_vec());
-        assert_eq!(stored_bet.team2.to_owned(), "team2".as_bytes().to_vec());
+        assert_eq!(
+            outcome_names(match_id),
+            vec![b"team1".to_vec(), b"team2".to_vec(), DRAW.to_vec()]
+        );
         assert_eq!(stored_bet.result, None);
     });
 }
@@ -36,14 +187,17 @@ fn creates_a_match() {
 fn error_creating_same_match() {
     new_test_ext().execute_with(|| {
         let _ = create_match(1, "team1", "team2", 10, 10);
+        let (team1, team2) = (team("team1"), team("team2"));
         // Do not allow other user to create a match with same specs of a previous one.
         assert_noop!(
             Betting::create_match_to_bet(
                 RuntimeOrigin::signed(2),
-                "team1".as_bytes().to_vec(),
-                "team2".as_bytes().to_vec(),
+                team1,
+                team2,
                 10,
-                10
+                10,
+                Permill::zero(),
+                None
             ),
             Error::<Test>::MatchAlreadyExists
         );
@@ -51,20 +205,52 @@ fn error_creating_same_match() {
 }
 
 #[test]
-fn error_creating_a_match_with_an_open_match() {
+fn matches_between_the_same_teams_at_the_same_time_are_duplicates() {
     new_test_ext().execute_with(|| {
-        let _ = create_match(1, "team1", "team2", 10, 10);
-        // Ensure the expected error is thrown when the user tries to create a second match.
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        let (team1, team2) = (team("team1"), team("team2"));
+        assert_eq!(
+            Betting::get_matches(match_id).unwrap().teams,
+            Some((team1, team2))
+        );
+
+        // Whatever the order of the teams and the length of the match
         assert_noop!(
             Betting::create_match_to_bet(
-                RuntimeOrigin::signed(1),
-                "team3".as_bytes().to_vec(),
-                "team3".as_bytes().to_vec(),
+                RuntimeOrigin::signed(2),
+                team2,
+                team1,
+                10,
                 20,
-                20
+                Permill::zero(),
+                None
             ),
-            Error::<Test>::OriginHasAlreadyOpenMatch
+            Error::<Test>::MatchAlreadyExists
         );
+        let _ = create_match(2, "team2", "team1", 30, 10);
+    });
+}
+
+#[test]
+fn creates_several_matches_from_the_same_account() {
+    new_test_ext().execute_with(|| {
+        let first_match_id = create_match(1, "team1", "team2", 10, 10);
+        let second_match_id = create_match(1, "team3", "team4", 20, 20);
+        // Every match gets its own sequential id.
+        assert_eq!(first_match_id, 0);
+        assert_eq!(second_match_id, 1);
+        assert_eq!(Betting::next_match_id(), 2);
+        assert_eq!(
+            Betting::get_matches(second_match_id).unwrap().outcomes[0]
+                .name
+                .to_vec(),
+            b"team3".to_vec()
+        );
+        // Both matches are indexed under their creator.
+        assert!(MatchesByCreator::<Test>::contains_key(1, first_match_id));
+        assert!(MatchesByCreator::<Test>::contains_key(1, second_match_id));
+        // Both deposits are reserved.
+        assert_eq!(Balances::reserved_balance(1), 20);
     });
 }
 
@@ -72,47 +258,208 @@ fn error_creating_a_match_with_an_open_match() {
 fn error_creating_a_match_that_has_finished() {
     new_test_ext().execute_with(|| {
         System::set_block_number(40);
+        let (team1, team2) = (team("team1"), team("team2"));
         // Ensure the expected error is thrown when the user tries to create a match with a wrong time.
         assert_noop!(
             Betting::create_match_to_bet(
                 RuntimeOrigin::signed(1),
-                "team1".as_bytes().to_vec(),
-                "team2".as_bytes().to_vec(),
+                team1,
+                team2,
                 10,
-                10
+                10,
+                Permill::zero(),
+                None
             ),
             Error::<Test>::TimeMatchOver
         );
     });
 }
 
+#[test]
+fn error_creating_a_match_between_teams() {
+    new_test_ext().execute_with(|| {
+        let team1 = team("team1");
+        assert_noop!(
+            Betting::create_match_to_bet(
+                RuntimeOrigin::signed(1),
+                team1,
+                team1,
+                10,
+                10,
+                Permill::zero(),
+                None
+            ),
+            Error::<Test>::DuplicateTeam
+        );
+        assert_noop!(
+            Betting::create_match_to_bet(
+                RuntimeOrigin::signed(1),
+                team1,
+                99,
+                10,
+                10,
+                Permill::zero(),
+                None
+            ),
+            Error::<Test>::UnknownTeam
+        );
+    });
+}
+
+#[test]
+fn registers_and_updates_teams() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Betting::register_team(
+            RawOrigin::Root.into(),
+            b"Real Madrid".to_vec(),
+            Some(b"Spain".to_vec())
+        ));
+        let name = TeamName::<Test>::try_from(b"Real Madrid".to_vec()).unwrap();
+        System::assert_last_event(RuntimeEvent::Betting(Event::TeamRegistered(0, name)));
+        let key = TeamName::<Test>::try_from(b"real madrid".to_vec()).unwrap();
+        assert_eq!(Betting::get_team_id(&key), Some(0));
+
+        // The same name with another case or spacing is the same team
+        assert_noop!(
+            Betting::register_team(RawOrigin::Root.into(), b" real  MADRID ".to_vec(), None),
+            Error::<Test>::TeamAlreadyRegistered
+        );
+
+        assert_ok!(Betting::update_team(
+            RawOrigin::Root.into(),
+            0,
+            b"Real Madrid CF".to_vec(),
+            None
+        ));
+        let team = Betting::get_team(0).unwrap();
+        assert_eq!(team.name.to_vec(), b"Real Madrid CF".to_vec());
+        assert_eq!(team.metadata, None);
+        assert_eq!(Betting::get_team_id(&key), None);
+        assert_ok!(Betting::register_team(
+            RawOrigin::Root.into(),
+            b"Real Madrid".to_vec(),
+            None
+        ));
+        assert_eq!(Betting::get_team_id(&key), Some(1));
+    });
+}
+
+#[test]
+fn error_registering_a_team() {
+    new_test_ext().execute_with(|| {
+        let real_madrid = team("Real Madrid");
+        let barcelona = team("Barcelona");
+        assert_noop!(
+            Betting::register_team(RuntimeOrigin::signed(1), b"Sevilla".to_vec(), None),
+            BadOrigin
+        );
+        assert_noop!(
+            Betting::register_team(RawOrigin::Root.into(), b"  ".to_vec(), None),
+            Error::<Test>::EmptyTeamName
+        );
+        assert_noop!(
+            Betting::register_team(RawOrigin::Root.into(), vec![b'a'; 65], None),
+            Error::<Test>::TeamNameTooLong
+        );
+        assert_noop!(
+            Betting::register_team(
+                RawOrigin::Root.into(),
+                b"Sevilla".to_vec(),
+                Some(vec![b'a'; 65])
+            ),
+            Error::<Test>::TeamMetadataTooLong
+        );
+        assert_noop!(
+            Betting::update_team(RawOrigin::Root.into(), 99, b"Sevilla".to_vec(), None),
+            Error::<Test>::UnknownTeam
+        );
+        assert_noop!(
+            Betting::update_team(
+                RawOrigin::Root.into(),
+                barcelona,
+                b"real madrid".to_vec(),
+                None
+            ),
+            Error::<Test>::TeamAlreadyRegistered
+        );
+        // A team keeps its name with another case
+        assert_ok!(Betting::update_team(
+            RawOrigin::Root.into(),
+            real_madrid,
+            b"REAL MADRID".to_vec(),
+            None
+        ));
+    });
+}
+
 #[test]
 fn creates_a_bet() {
     new_test_ext().execute_with(|| {
         let match_id = create_match(1, "team1", "team2", 10, 10);
-        assert_eq!(Balances::free_balance(Test::account_id()), 0);
+        // The pot is seeded with the existential deposit
+        let pot = Betting::pot_account(match_id);
+        assert_eq!(Balances::free_balance(pot), 2);
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(2),
             match_id,
             100,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
+        ));
+        assert_eq!(Balances::free_balance(pot), 102);
+        assert_eq!(Betting::get_matches(match_id).unwrap().bet_count, 1);
+        assert_eq!(
+            Betting::get_bet((match_id, 2, MatchResult::Team1Victory.index())),
+            Some(Bet {
+                amount: 100,
+                claimed: false
+            })
+        );
+    });
+}
+
+#[test]
+fn every_match_has_its_own_pot() {
+    new_test_ext().execute_with(|| {
+        let first_match_id = create_match(1, "team1", "team2", 10, 10);
+        let second_match_id = create_match(1, "team3", "team4", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            first_match_id,
+            100,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            second_match_id,
+            30,
+            MatchResult::Draw.index()
         ));
-        assert_eq!(Balances::free_balance(Test::account_id()), 100);
-        assert_eq!(Betting::get_matches(match_id).unwrap().bets.len(), 1);
+        let first_pot = Betting::pot_account(first_match_id);
+        let second_pot = Betting::pot_account(second_match_id);
+        assert_eq!(Balances::free_balance(first_pot), 102);
+        assert_eq!(Balances::free_balance(second_pot), 32);
+
+        // The pot account is reaped when its match is deleted
+        assert_ok!(Betting::cancel_match(
+            RuntimeOrigin::signed(1),
+            first_match_id
+        ));
+        assert_eq!(System::account_exists(&first_pot), false);
+        assert_eq!(Balances::free_balance(second_pot), 32);
     });
 }
 
 #[test]
 fn error_betting_a_match_does_not_exist() {
     new_test_ext().execute_with(|| {
-        let match_id = ensure_signed(RuntimeOrigin::signed(1)).unwrap();
+        let match_id = Betting::next_match_id();
         // Ensure the expected error is thrown when the user tries to create a bet in a match doesn't exist.
         assert_noop!(
             Betting::bet(
                 RuntimeOrigin::signed(2),
                 match_id,
                 100,
-                MatchResult::Team1Victory
+                MatchResult::Team1Victory.index()
             ),
             Error::<Test>::MatchDoesNotExist
         );
@@ -130,13 +477,133 @@ fn error_betting_a_match_has_start() {
                 RuntimeOrigin::signed(2),
                 match_id,
                 100,
-                MatchResult::Team1Victory
+                MatchResult::Team1Victory.index()
             ),
             Error::<Test>::MatchHasStarted
         );
     });
 }
 
+#[test]
+fn error_betting_zero() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        // An empty bet would take one of the positions of the match
+        assert_noop!(
+            Betting::bet(
+                RuntimeOrigin::signed(2),
+                match_id,
+                0,
+                MatchResult::Team1Victory.index()
+            ),
+            Error::<Test>::ZeroAmount
+        );
+    });
+}
+
+#[test]
+fn adds_to_a_position() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            100,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            50,
+            MatchResult::Team1Victory.index()
+        ));
+        let betting_match = Betting::get_matches(match_id).unwrap();
+        assert_eq!(betting_match.bet_count, 1);
+        assert_eq!(
+            betting_match.total_on(MatchResult::Team1Victory.index()),
+            150
+        );
+        assert_eq!(
+            Betting::get_bet((match_id, 2, MatchResult::Team1Victory.index())),
+            Some(Bet {
+                amount: 150,
+                claimed: false
+            })
+        );
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 152);
+    });
+}
+
+#[test]
+fn hedges_across_results() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            20,
+            MatchResult::Team2Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            30,
+            MatchResult::Team1Victory.index()
+        ));
+        // Adding to an existing position does not count against the limit of bets
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_noop!(
+            Betting::bet(
+                RuntimeOrigin::signed(3),
+                match_id,
+                10,
+                MatchResult::Draw.index()
+            ),
+            Error::<Test>::MaxBets
+        );
+
+        let mut positions = Betting::positions(match_id, &2);
+        positions.sort();
+        assert_eq!(
+            positions,
+            vec![
+                (MatchResult::Team1Victory.index(), 20),
+                (MatchResult::Team2Victory.index(), 20)
+            ]
+        );
+        let betting_match = Betting::get_matches(match_id).unwrap();
+        assert_eq!(betting_match.bet_count, 3);
+        assert_eq!(betting_match.total(), 70);
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        // 70 UNITS were bet, 50 on the winning result: the player 2 paid 40 and gets back 28
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 12);
+        // The player 3 paid 30 and gets back 42
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE + 12);
+    });
+}
+
 #[test]
 fn error_max_number_bets() {
     new_test_ext().execute_with(|| {
@@ -146,19 +613,30 @@ fn error_max_number_bets() {
             RuntimeOrigin::signed(2),
             match_id,
             100,
-            MatchResult::Team1Victory,
+            MatchResult::Team1Victory.index(),
         )
         .ok();
         Betting::bet(
             RuntimeOrigin::signed(3),
             match_id,
             100,
-            MatchResult::Team2Victory,
+            MatchResult::Team2Victory.index(),
+        )
+        .ok();
+        Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            50,
+            MatchResult::Draw.index(),
         )
         .ok();
-        Betting::bet(RuntimeOrigin::signed(3), match_id, 50, MatchResult::Draw).ok();
         assert_noop!(
-            Betting::bet(RuntimeOrigin::signed(4), match_id, 100, MatchResult::Draw),
+            Betting::bet(
+                RuntimeOrigin::signed(4),
+                match_id,
+                100,
+                MatchResult::Draw.index()
+            ),
             Error::<Test>::MaxBets
         );
     });
@@ -170,79 +648,215 @@ fn error_no_funds_to_bet() {
         let match_id = create_match(1, "team1", "team2", 10, 10);
         // Ensure the expected error is thrown when the user tries to bet without funds.
         assert_noop!(
-            Betting::bet(RuntimeOrigin::signed(5), match_id, 100, MatchResult::Draw),
+            Betting::bet(
+                RuntimeOrigin::signed(5),
+                match_id,
+                100,
+                MatchResult::Draw.index()
+            ),
             BalancesError::<Test, _>::InsufficientBalance,
         );
-        assert_eq!(Betting::get_matches(match_id).unwrap().bets.len(), 0);
+        assert_eq!(Betting::get_matches(match_id).unwrap().bet_count, 0);
     });
 }
 
 #[test]
-fn set_result_of_match() {
+fn cancel_bet_before_start() {
     new_test_ext().execute_with(|| {
         let match_id = create_match(1, "team1", "team2", 10, 10);
-        // Set the result of that match when it ends.
-        System::set_block_number(22);
-        assert_ok!(Betting::set_result(
-            RawOrigin::Root.into(),
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            100,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::cancel_bet(
+            RuntimeOrigin::signed(2),
             match_id,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 2);
         assert_eq!(
-            Betting::get_matches(match_id).unwrap().result,
-            Some(MatchResult::Team1Victory)
-        );
-    });
-}
-
-#[test]
-fn error_set_result_no_root() {
-    new_test_ext().execute_with(|| {
-        let match_id = create_match(1, "team1", "team2", 10, 10);
-        assert_noop!(
-            Betting::set_result(
-                RuntimeOrigin::signed(2),
-                match_id,
-                MatchResult::Team1Victory
-            ),
-            BadOrigin
+            Betting::get_bet((match_id, 2, MatchResult::Team1Victory.index())),
+            None
         );
+        let betting_match = Betting::get_matches(match_id).unwrap();
+        assert_eq!(betting_match.bet_count, 0);
+        assert_eq!(betting_match.total(), 0);
     });
 }
 
 #[test]
-fn error_set_result_of_match_not_end() {
+fn reduce_bet_pays_cancellation_fee_to_creator() {
     new_test_ext().execute_with(|| {
+        CancellationFee::set(Permill::from_percent(10));
         let match_id = create_match(1, "team1", "team2", 10, 10);
-        assert_noop!(
-            Betting::set_result(RawOrigin::Root.into(), match_id, MatchResult::Team1Victory),
-            Error::<Test>::TimeMatchNotOver
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            100,
+            MatchResult::Draw.index()
+        ));
+        assert_ok!(Betting::reduce_bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            MatchResult::Draw.index(),
+            40
+        ));
+        // 40 UNITS are taken off the bet, 4 go to the creator and 36 back to the bettor
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 64);
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE - 12 + 4);
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 62);
+        assert_eq!(
+            Betting::get_bet((match_id, 2, MatchResult::Draw.index())),
+            Some(Bet {
+                amount: 60,
+                claimed: false
+            })
         );
+        let betting_match = Betting::get_matches(match_id).unwrap();
+        assert_eq!(betting_match.bet_count, 1);
+        assert_eq!(betting_match.total_on(MatchResult::Draw.index()), 60);
     });
 }
 
 #[test]
-fn distribute_winnings() {
+fn error_reduce_bet() {
     new_test_ext().execute_with(|| {
         let match_id = create_match(1, "team1", "team2", 10, 10);
-
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            100,
+            MatchResult::Draw.index()
+        ));
+        assert_noop!(
+            Betting::cancel_bet(
+                RuntimeOrigin::signed(3),
+                match_id,
+                MatchResult::Draw.index()
+            ),
+            Error::<Test>::NoBet
+        );
+        assert_noop!(
+            Betting::reduce_bet(
+                RuntimeOrigin::signed(2),
+                match_id,
+                MatchResult::Team1Victory.index(),
+                10
+            ),
+            Error::<Test>::NoBet
+        );
+        assert_noop!(
+            Betting::reduce_bet(
+                RuntimeOrigin::signed(2),
+                match_id,
+                MatchResult::Draw.index(),
+                101
+            ),
+            Error::<Test>::AmountExceedsBet
+        );
+        System::set_block_number(10);
+        assert_noop!(
+            Betting::cancel_bet(
+                RuntimeOrigin::signed(2),
+                match_id,
+                MatchResult::Draw.index()
+            ),
+            Error::<Test>::MatchHasStarted
+        );
+    });
+}
+
+#[test]
+fn set_result_of_match() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        // Set the result of that match when it ends.
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_eq!(
+            Betting::get_matches(match_id).unwrap().result,
+            Some(MatchResult::Team1Victory.index())
+        );
+    });
+}
+
+#[test]
+fn set_result_of_match_by_oracle() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        System::set_block_number(22);
+        // A non-root oracle account configured as `ResultOrigin` can report the result.
+        assert_ok!(Betting::set_result(
+            RuntimeOrigin::signed(ORACLE),
+            match_id,
+            MatchResult::Draw.index()
+        ));
+        assert_eq!(
+            Betting::get_matches(match_id).unwrap().result,
+            Some(MatchResult::Draw.index())
+        );
+    });
+}
+
+#[test]
+fn error_set_result_no_root() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::set_result(
+                RuntimeOrigin::signed(2),
+                match_id,
+                MatchResult::Team1Victory.index()
+            ),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn error_set_result_of_match_not_end() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::set_result(
+                RawOrigin::Root.into(),
+                match_id,
+                MatchResult::Team1Victory.index()
+            ),
+            Error::<Test>::TimeMatchNotOver
+        );
+    });
+}
+
+#[test]
+fn distribute_winnings() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(2),
             match_id,
             10,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(3),
             match_id,
             10,
-            MatchResult::Team2Victory
+            MatchResult::Team2Victory.index()
         ));
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(4),
             match_id,
             30,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
 
         // Set the result of that match when it ends.
@@ -250,11 +864,15 @@ fn distribute_winnings() {
         assert_ok!(Betting::set_result(
             RawOrigin::Root.into(),
             match_id,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
 
-        // The owner distributes the prizes
-        assert_ok!(Betting::distribute_winnings(RuntimeOrigin::signed(1)));
+        // The owner distributes the prizes once the result can no longer be disputed
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
         // Check that the prizes has been distributed properly.
         // With the maths there were 50 UNITS bet, 10 to the Team2 that lost and has to be shared by the rest
 
@@ -267,8 +885,13 @@ fn distribute_winnings() {
         // The third player deposit 30 and win back 37, has to have 7 UNITS more
         assert_eq!(Balances::free_balance(4), 1000000000000007);
 
+        // The UNIT left by the rounding goes to the owner
+        assert_eq!(Balances::free_balance(1), 1000000000000001);
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 0);
+
         // Check that the matches has been deleted after the distribution
         assert_eq!(Betting::get_matches(match_id), None);
+        assert!(!MatchesByCreator::<Test>::contains_key(1, match_id));
     });
 }
 
@@ -281,35 +904,73 @@ fn error_distribute_winnings_no_match() {
             RuntimeOrigin::signed(2),
             match_id,
             10,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(3),
             match_id,
             10,
-            MatchResult::Team2Victory
+            MatchResult::Team2Victory.index()
         ));
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(4),
             match_id,
             30,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
         // Set the result of that match when it ends.
         System::set_block_number(22);
         assert_ok!(Betting::set_result(
             RawOrigin::Root.into(),
             match_id,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
 
         assert_noop!(
-            Betting::distribute_winnings(RuntimeOrigin::signed(3)),
+            Betting::distribute_winnings(RuntimeOrigin::signed(1), match_id + 1),
             Error::<Test>::MatchDoesNotExist
         );
     });
 }
 
+#[test]
+fn error_distribute_winnings_not_creator() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            10,
+            MatchResult::Team2Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(4),
+            match_id,
+            30,
+            MatchResult::Team1Victory.index()
+        ));
+        // Set the result of that match when it ends.
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+
+        assert_noop!(
+            Betting::distribute_winnings(RuntimeOrigin::signed(3), match_id),
+            Error::<Test>::NotMatchCreator
+        );
+    });
+}
+
 #[test]
 fn error_distribute_winnings_no_result() {
     new_test_ext().execute_with(|| {
@@ -319,24 +980,24 @@ fn error_distribute_winnings_no_result() {
             RuntimeOrigin::signed(2),
             match_id,
             10,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(3),
             match_id,
             10,
-            MatchResult::Team2Victory
+            MatchResult::Team2Victory.index()
         ));
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(4),
             match_id,
             30,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
 
         // The owner tries to distributes the prizes
         assert_noop!(
-            Betting::distribute_winnings(RuntimeOrigin::signed(1)),
+            Betting::distribute_winnings(RuntimeOrigin::signed(1), match_id),
             Error::<Test>::MatchNotResult
         );
     });
@@ -347,24 +1008,28 @@ fn deposit_when_creates_match() {
     new_test_ext().execute_with(|| {
         let balance_before_deposit = Balances::free_balance(1);
         let match_id = create_match(1, "team1", "team2", 10, 10);
-        // Check the free balance from the account has been deducted
-        assert_eq!(Balances::free_balance(1), balance_before_deposit - 10);
+        // Check the deposit and the seed of the pot have been deducted from the account
+        assert_eq!(Balances::free_balance(1), balance_before_deposit - 12);
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(2),
             match_id,
             10,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
         // Set the result of that match when it ends.
         System::set_block_number(22);
         assert_ok!(Betting::set_result(
             RawOrigin::Root.into(),
             match_id,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
 
-        // The owner distributes the prizes
-        assert_ok!(Betting::distribute_winnings(RuntimeOrigin::signed(1)));
+        // The owner distributes the prizes once the result can no longer be disputed
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
 
         // Check the free balance from the account has been returned
         assert_eq!(Balances::free_balance(1), balance_before_deposit);
@@ -376,33 +1041,2741 @@ fn transfer_below_existential_deposit() {
     new_test_ext().execute_with(|| {
         let balance_before_deposit = Balances::free_balance(1);
         let match_id = create_match(1, "team1", "team2", 10, 10);
-        // Check the free balance from the account has been deducted
-        assert_eq!(Balances::free_balance(1), balance_before_deposit - 10);
+        // Check the deposit and the seed of the pot have been deducted from the account
+        assert_eq!(Balances::free_balance(1), balance_before_deposit - 12);
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(2),
             match_id,
             9,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
         assert_ok!(Betting::bet(
             RuntimeOrigin::signed(3),
             match_id,
             1,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
         // Set the result of that match when it ends.
         System::set_block_number(22);
         assert_ok!(Betting::set_result(
             RawOrigin::Root.into(),
             match_id,
-            MatchResult::Team1Victory
+            MatchResult::Team1Victory.index()
         ));
 
-        // The owner distributes the prizes
-        assert_ok!(Betting::distribute_winnings(RuntimeOrigin::signed(1)));
+        // The owner distributes the prizes once the result can no longer be disputed
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
 
         // Check the free balance from the account has been returned
         assert_eq!(Balances::free_balance(1),balance_before_deposit);
        // println!("{}",Balances::free_balance(1));
     });
 }
+
+#[test]
+fn cancel_match_by_creator_refunds_bets() {
+    new_test_ext().execute_with(|| {
+        let balance_before_deposit = Balances::free_balance(1);
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            30,
+            MatchResult::Draw.index()
+        ));
+
+        assert_ok!(Betting::cancel_match(RuntimeOrigin::signed(1), match_id));
+
+        // Every bettor gets the stake back and the creator the deposit
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(1), balance_before_deposit);
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 0);
+
+        // The match is deleted and the same specs can be used again
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert!(!MatchesByCreator::<Test>::contains_key(1, match_id));
+        let _ = create_match(2, "team1", "team2", 10, 10);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchCancelled(match_id)));
+    });
+}
+
+#[test]
+fn cancel_match_by_privileged_origin_after_start() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(15);
+        assert_ok!(Betting::cancel_match(RawOrigin::Root.into(), match_id));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Betting::get_matches(match_id), None);
+    });
+}
+
+#[test]
+fn error_cancel_match_by_creator_after_start() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        System::set_block_number(10);
+        assert_noop!(
+            Betting::cancel_match(RuntimeOrigin::signed(1), match_id),
+            Error::<Test>::MatchHasStarted
+        );
+    });
+}
+
+#[test]
+fn error_cancel_match_after_winnings_claimed() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(4),
+            match_id,
+            20,
+            MatchResult::Team2Victory.index()
+        ));
+
+        System::set_block_number(20);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(25);
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+
+        // The pot no longer holds the stakes of every bettor
+        assert_noop!(
+            Betting::cancel_match(RawOrigin::Root.into(), match_id),
+            Error::<Test>::WinningsAlreadyClaimed
+        );
+    });
+}
+
+#[test]
+fn error_cancel_match_not_creator() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::cancel_match(RuntimeOrigin::signed(2), match_id),
+            Error::<Test>::NotMatchCreator
+        );
+    });
+}
+
+#[test]
+fn refund_match_without_result_after_timeout() {
+    new_test_ext().execute_with(|| {
+        let balance_before_deposit = Balances::free_balance(1);
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            30,
+            MatchResult::Draw.index()
+        ));
+
+        // The match ends at block 20 and its result can be set until block 50
+        System::set_block_number(50);
+        assert_ok!(Betting::refund_match(RuntimeOrigin::signed(4), match_id));
+
+        // Every bettor gets the stake back and the caller the incentive out of the deposit
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(4), INIT_BALANCE + 4);
+        assert_eq!(Balances::free_balance(1), balance_before_deposit - 4);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 0);
+        assert_eq!(Betting::get_matches(match_id), None);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchTimedOut(match_id, 4, 4)));
+    });
+}
+
+#[test]
+fn error_refund_match() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        System::set_block_number(49);
+        assert_noop!(
+            Betting::refund_match(RuntimeOrigin::signed(4), match_id),
+            Error::<Test>::ResultTimeoutNotOver
+        );
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Draw.index()
+        ));
+        System::set_block_number(50);
+        assert_noop!(
+            Betting::refund_match(RuntimeOrigin::signed(4), match_id),
+            Error::<Test>::ResultAlreadySet
+        );
+    });
+}
+
+#[test]
+fn on_idle_settles_matches_once_final() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+
+        // The result can still be disputed
+        System::set_block_number(26);
+        Betting::on_idle(26, Weight::MAX);
+        assert!(Betting::get_matches(match_id).is_some());
+
+        // There is no weight left in the block
+        System::set_block_number(27);
+        Betting::on_idle(27, Weight::zero());
+        assert!(Betting::get_matches(match_id).is_some());
+
+        // The weight left only covers paying one bet of the match, the other is left for later
+        Betting::on_idle(27, <() as WeightInfo>::finalise_queued_match(1));
+        assert!(Betting::get_matches(match_id).is_some());
+        assert_eq!(Bets::<Test>::iter_prefix((match_id,)).count(), 1);
+        assert_eq!(SettlementQueue::<Test>::iter_prefix(27).count(), 1);
+
+        Betting::on_idle(27, Weight::MAX);
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 10);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 10);
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchClosed(match_id)));
+
+        // The entry queued to refund the match if it never got a result is dropped
+        System::set_block_number(50);
+        Betting::on_idle(50, Weight::MAX);
+        assert_eq!(SettlementQueue::<Test>::iter().count(), 0);
+        assert_eq!(SettlementCursor::<Test>::get(), Some(51));
+    });
+}
+
+#[test]
+fn on_idle_refunds_matches_without_result() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+
+        System::set_block_number(50);
+        Betting::on_idle(50, Weight::MAX);
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchTimedOut(match_id, 1, 0)));
+    });
+}
+
+#[test]
+fn on_idle_refunds_matches_without_result_a_page_at_a_time() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        for bettor in 2..=4 {
+            assert_ok!(Betting::bet(
+                RuntimeOrigin::signed(bettor),
+                match_id,
+                10,
+                MatchResult::Team1Victory.index()
+            ));
+        }
+
+        // The match is voided so it can not get a result once some bets are refunded
+        System::set_block_number(50);
+        Betting::on_idle(50, <() as WeightInfo>::finalise_queued_match(2));
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchTimedOut(match_id, 1, 0)));
+        assert_eq!(Bets::<Test>::iter_prefix((match_id,)).count(), 1);
+        let refunded = (2..=4)
+            .filter(|bettor| Balances::free_balance(bettor) == INIT_BALANCE)
+            .count();
+        assert_eq!(refunded, 2);
+        assert_noop!(
+            Betting::set_result(RawOrigin::Root.into(), match_id, MatchResult::Draw.index()),
+            Error::<Test>::ResultAlreadySet
+        );
+        assert_noop!(
+            Betting::cancel_match(RawOrigin::Root.into(), match_id),
+            Error::<Test>::WinningsAlreadyClaimed
+        );
+
+        System::set_block_number(51);
+        Betting::on_idle(51, Weight::MAX);
+        assert_eq!(Betting::get_matches(match_id), None);
+        for bettor in 2..=4 {
+            assert_eq!(Balances::free_balance(&bettor), INIT_BALANCE);
+        }
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchClosed(match_id)));
+    });
+}
+
+#[test]
+fn scheduled_settlement_retries_until_result_is_final() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            10,
+            MatchResult::Team2Victory.index()
+        ));
+        assert_ok!(Betting::schedule_settlement(
+            RuntimeOrigin::signed(1),
+            match_id,
+            5
+        ));
+        assert_eq!(Betting::get_scheduled_settlement(match_id), Some(25));
+
+        run_to_block(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+
+        // The result can still be disputed, the settlement is tried again later
+        run_to_block(25);
+        assert!(Betting::get_matches(match_id).is_some());
+        assert_eq!(Betting::get_scheduled_settlement(match_id), Some(30));
+        System::assert_has_event(RuntimeEvent::Betting(Event::SettlementScheduled(
+            match_id, 30,
+        )));
+
+        run_to_block(30);
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert_eq!(Betting::get_scheduled_settlement(match_id), None);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 10);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 10);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchClosed(match_id)));
+    });
+}
+
+#[test]
+fn scheduled_settlement_refunds_match_without_result() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::schedule_settlement(
+            RuntimeOrigin::signed(1),
+            match_id,
+            5
+        ));
+
+        run_to_block(49);
+        assert!(Betting::get_matches(match_id).is_some());
+
+        // The result timeout is over at block 50
+        run_to_block(50);
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchTimedOut(match_id, 1, 0)));
+    });
+}
+
+#[test]
+fn error_schedule_settlement() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::schedule_settlement(RuntimeOrigin::signed(2), match_id, 5),
+            Error::<Test>::NotMatchCreator
+        );
+        assert_ok!(Betting::schedule_settlement(
+            RuntimeOrigin::signed(1),
+            match_id,
+            5
+        ));
+        assert_noop!(
+            Betting::schedule_settlement(RuntimeOrigin::signed(1), match_id, 5),
+            Error::<Test>::SettlementAlreadyScheduled
+        );
+        assert_noop!(
+            Betting::settle_scheduled(RuntimeOrigin::signed(1), match_id),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn void_match_refunds_every_bet() {
+    new_test_ext().execute_with(|| {
+        let balances = || [1, 2, 3, 4].map(Balances::free_balance);
+        let before = balances();
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        let total_id = derive_market(1, match_id, ScoreMarket::Total(10));
+
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            25,
+            MatchResult::Draw.index()
+        ));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(4), total_id, 40, 0));
+
+        System::set_block_number(22);
+        assert_ok!(Betting::void_match(RawOrigin::Root.into(), match_id));
+        // The derived markets are voided with the match
+        assert!(Betting::get_matches(total_id).unwrap().voided);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchVoided(total_id)));
+
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(4), total_id));
+
+        // Every bet is refunded at face value, and the creator gets the deposits back
+        assert_eq!(balances(), before);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert_eq!(Betting::get_matches(total_id), None);
+    });
+}
+
+#[test]
+fn resolve_dispute_overturns_void() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team2Victory.index()
+        ));
+        System::set_block_number(22);
+        assert_ok!(Betting::void_match(RawOrigin::Root.into(), match_id));
+        assert_ok!(Betting::dispute_result(RuntimeOrigin::signed(3), match_id));
+        assert_ok!(Betting::resolve_dispute(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team2Victory.index()
+        ));
+
+        let betting_match = Betting::get_matches(match_id).unwrap();
+        assert!(!betting_match.voided);
+        assert_eq!(
+            betting_match.settlement(),
+            Some(Settlement::Win(MatchResult::Team2Victory.index()))
+        );
+    });
+}
+
+#[test]
+fn error_void_match() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::void_match(RuntimeOrigin::signed(1), match_id),
+            BadOrigin
+        );
+        assert_noop!(
+            Betting::void_match(RawOrigin::Root.into(), match_id),
+            Error::<Test>::TimeMatchNotOver
+        );
+        System::set_block_number(22);
+        assert_ok!(Betting::void_match(RawOrigin::Root.into(), match_id));
+        assert_noop!(
+            Betting::void_match(RawOrigin::Root.into(), match_id),
+            Error::<Test>::ResultAlreadySet
+        );
+        assert_noop!(
+            Betting::set_result(RawOrigin::Root.into(), match_id, MatchResult::Draw.index()),
+            Error::<Test>::ResultAlreadySet
+        );
+    });
+}
+
+#[test]
+fn error_set_result_twice() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        assert_noop!(
+            Betting::set_result(
+                RawOrigin::Root.into(),
+                match_id,
+                MatchResult::Team2Victory.index()
+            ),
+            Error::<Test>::ResultAlreadySet
+        );
+    });
+}
+
+#[test]
+fn error_distribute_winnings_during_dispute_period() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        System::set_block_number(26);
+        assert_noop!(
+            Betting::distribute_winnings(RuntimeOrigin::signed(1), match_id),
+            Error::<Test>::DisputePeriodNotOver
+        );
+    });
+}
+
+#[test]
+fn dispute_result_reserves_bond() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        assert_ok!(Betting::dispute_result(RuntimeOrigin::signed(3), match_id));
+        assert_eq!(Balances::reserved_balance(3), 50);
+        assert_eq!(Betting::get_disputes(match_id).len(), 1);
+        assert_noop!(
+            Betting::dispute_result(RuntimeOrigin::signed(3), match_id),
+            Error::<Test>::AlreadyDisputed
+        );
+        // While the dispute is open the winnings can not be distributed
+        System::set_block_number(30);
+        assert_noop!(
+            Betting::distribute_winnings(RuntimeOrigin::signed(1), match_id),
+            Error::<Test>::MatchDisputed
+        );
+    });
+}
+
+#[test]
+fn error_dispute_result_after_dispute_period() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        System::set_block_number(27);
+        assert_noop!(
+            Betting::dispute_result(RuntimeOrigin::signed(3), match_id),
+            Error::<Test>::DisputePeriodOver
+        );
+    });
+}
+
+#[test]
+fn error_dispute_result_without_result() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::dispute_result(RuntimeOrigin::signed(3), match_id),
+            Error::<Test>::MatchNotResult
+        );
+    });
+}
+
+#[test]
+fn resolve_dispute_upholds_result() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        assert_ok!(Betting::dispute_result(RuntimeOrigin::signed(3), match_id));
+        let issuance_before = Balances::total_issuance();
+
+        assert_ok!(Betting::resolve_dispute(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+
+        // The bond has been slashed
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 10 - 50);
+        assert_eq!(Balances::total_issuance(), issuance_before - 50);
+        assert_eq!(
+            Betting::get_matches(match_id).unwrap().result,
+            Some(MatchResult::Team1Victory.index())
+        );
+        System::assert_has_event(RuntimeEvent::Betting(Event::ResultUpheld(match_id)));
+
+        // The dispute period is closed and the winnings can be distributed
+        assert_noop!(
+            Betting::dispute_result(RuntimeOrigin::signed(4), match_id),
+            Error::<Test>::DisputePeriodOver
+        );
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 10);
+    });
+}
+
+#[test]
+fn resolve_dispute_overturns_result() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        assert_ok!(Betting::dispute_result(RuntimeOrigin::signed(3), match_id));
+
+        assert_ok!(Betting::resolve_dispute(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team2Victory.index()
+        ));
+
+        // The bond has been returned and the result changed
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(
+            Betting::get_matches(match_id).unwrap().result,
+            Some(MatchResult::Team2Victory.index())
+        );
+        System::assert_has_event(RuntimeEvent::Betting(Event::ResultOverturned(
+            match_id,
+            MatchResult::Team2Victory.index(),
+        )));
+
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE + 10);
+    });
+}
+
+#[test]
+fn error_resolve_dispute_without_dispute() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        assert_noop!(
+            Betting::resolve_dispute(RawOrigin::Root.into(), match_id, MatchResult::Draw.index()),
+            Error::<Test>::NoDispute
+        );
+        assert_noop!(
+            Betting::resolve_dispute(
+                RuntimeOrigin::signed(ORACLE),
+                match_id,
+                MatchResult::Draw.index()
+            ),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn claim_winnings() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            10,
+            MatchResult::Team2Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(4),
+            match_id,
+            30,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+
+        // Winnings can not be claimed while the result can be disputed
+        assert_noop!(
+            Betting::claim_winnings(RuntimeOrigin::signed(2), match_id),
+            Error::<Test>::DisputePeriodNotOver
+        );
+        System::set_block_number(27);
+
+        // Every winner withdraws its own share
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 2);
+        System::assert_has_event(RuntimeEvent::Betting(Event::WinningsClaimed(
+            match_id, 2, 12,
+        )));
+        assert_noop!(
+            Betting::claim_winnings(RuntimeOrigin::signed(2), match_id),
+            Error::<Test>::NothingToClaim
+        );
+        assert_noop!(
+            Betting::claim_winnings(RuntimeOrigin::signed(3), match_id),
+            Error::<Test>::NothingToClaim
+        );
+        assert!(Betting::get_matches(match_id).is_some());
+
+        // The match is deleted once the last winner claims
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(4), match_id));
+        assert_eq!(Balances::free_balance(4), INIT_BALANCE + 7);
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchClosed(match_id)));
+    });
+}
+
+#[test]
+fn distribute_winnings_skips_claimed_bets() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            30,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(4),
+            match_id,
+            10,
+            MatchResult::Draw.index()
+        ));
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(27);
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        // The bettor that already claimed is not paid twice
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 2);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE + 7);
+        assert_eq!(Betting::get_matches(match_id), None);
+    });
+}
+
+#[test]
+fn reap_match_after_claim_period() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Team1Victory.index());
+        System::set_block_number(30);
+        assert_noop!(
+            Betting::reap_match(RuntimeOrigin::signed(4), match_id),
+            Error::<Test>::ClaimPeriodNotOver
+        );
+
+        // Anyone can delete the match once the claim period is over
+        System::set_block_number(47);
+        assert_ok!(Betting::reap_match(RuntimeOrigin::signed(4), match_id));
+        assert_eq!(Betting::get_matches(match_id), None);
+
+        // The unclaimed winnings go to the creator
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE + 20);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 10);
+    });
+}
+
+#[test]
+fn nobody_won_refunds_bets() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match_with_result(MatchResult::Draw.index());
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        // Every bettor gets the stake back
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE);
+        assert_eq!(Betting::get_matches(match_id), None);
+        System::assert_has_event(RuntimeEvent::Betting(Event::PotRefunded(match_id, 20)));
+    });
+}
+
+#[test]
+fn nobody_won_rolls_pot_over() {
+    new_test_ext().execute_with(|| {
+        NoWinner::set(NoWinnerPolicy::Rollover);
+        let match_id = create_match_with_result(MatchResult::Draw.index());
+        System::set_block_number(27);
+        // Any bettor can settle the match
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+        assert_eq!(Betting::get_rollover(1, None), Some((match_id, 20)));
+        System::assert_has_event(RuntimeEvent::Betting(Event::PotRolledOver(match_id, 1, 20)));
+
+        // The pot is added to the next match of the creator
+        let next_match_id = create_match(1, "team3", "team4", 30, 10);
+        assert_eq!(Betting::get_rollover(1, None), None);
+        assert_eq!(Betting::get_matches(next_match_id).unwrap().rollover, 20);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(4),
+            next_match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(41);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            next_match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(46);
+        assert_ok!(Betting::claim_winnings(
+            RuntimeOrigin::signed(4),
+            next_match_id
+        ));
+        assert_eq!(Balances::free_balance(4), INIT_BALANCE + 20);
+    });
+}
+
+#[test]
+fn nobody_won_sends_pot_to_treasury() {
+    new_test_ext().execute_with(|| {
+        NoWinner::set(NoWinnerPolicy::Treasury);
+        let match_id = create_match_with_result(MatchResult::Draw.index());
+        let issuance_before = Balances::total_issuance();
+        System::set_block_number(47);
+        assert_ok!(Betting::reap_match(RuntimeOrigin::signed(4), match_id));
+        assert_eq!(Balances::total_issuance(), issuance_before - 20);
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE);
+        System::assert_has_event(RuntimeEvent::Betting(Event::PotSentToTreasury(
+            match_id, 20,
+        )));
+    });
+}
+
+#[test]
+fn error_commission_too_high() {
+    new_test_ext().execute_with(|| {
+        let (team1, team2) = (team("team1"), team("team2"));
+        assert_noop!(
+            Betting::create_match_to_bet(
+                RuntimeOrigin::signed(1),
+                team1,
+                team2,
+                10,
+                10,
+                Permill::from_percent(11),
+                None
+            ),
+            Error::<Test>::CommissionTooHigh
+        );
+    });
+}
+
+#[test]
+fn fees_are_taken_before_paying_winners() {
+    new_test_ext().execute_with(|| {
+        PlatformFee::set(Permill::from_percent(5));
+        assert_ok!(Betting::create_match_to_bet(
+            RuntimeOrigin::signed(1),
+            team("team1"),
+            team("team2"),
+            10,
+            10,
+            Permill::from_percent(10),
+            None
+        ));
+        let match_id = 0;
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            100,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            100,
+            MatchResult::Team2Victory.index()
+        ));
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        let issuance_before = Balances::total_issuance();
+        System::set_block_number(27);
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+
+        // The platform fee goes to the treasury and the commission to the creator
+        assert_eq!(Balances::total_issuance(), issuance_before - 10);
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE + 20);
+        // The winner gets what is left of the pot
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 100 + 170);
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchSettled(
+            match_id, 200, 10, 20,
+        )));
+        assert_eq!(Betting::get_matches(match_id), None);
+    });
+}
+
+#[test]
+fn no_fees_without_winners() {
+    new_test_ext().execute_with(|| {
+        PlatformFee::set(Permill::from_percent(5));
+        let match_id = create_match_with_result(MatchResult::Draw.index());
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE);
+    });
+}
+
+#[test]
+fn error_creating_a_match_in_unknown_asset() {
+    new_test_ext().execute_with(|| {
+        let (team1, team2) = (team("team1"), team("team2"));
+        assert_noop!(
+            Betting::create_match_to_bet(
+                RuntimeOrigin::signed(1),
+                team1,
+                team2,
+                10,
+                10,
+                Permill::zero(),
+                Some(ASSET + 1)
+            ),
+            Error::<Test>::UnknownAsset
+        );
+    });
+}
+
+#[test]
+fn bets_and_payouts_in_assets() {
+    new_test_ext().execute_with(|| {
+        PlatformFee::set(Permill::from_percent(5));
+        assert_ok!(Betting::create_match_to_bet(
+            RuntimeOrigin::signed(1),
+            team("team1"),
+            team("team2"),
+            10,
+            10,
+            Permill::zero(),
+            Some(ASSET)
+        ));
+        let match_id = 0;
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            100,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            100,
+            MatchResult::Team2Victory.index()
+        ));
+        // The stakes are paid in the asset of the match
+        assert_eq!(Assets::balance(ASSET, 2), INIT_BALANCE - 100);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        let pot = Betting::pot_account(match_id);
+        assert_eq!(Assets::balance(ASSET, pot), 201);
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(27);
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+
+        // The platform fee goes to the asset treasury account
+        assert_eq!(Assets::balance(ASSET, TREASURY), 10);
+        assert_eq!(Assets::balance(ASSET, 2), INIT_BALANCE - 100 + 190);
+        assert_eq!(Assets::balance(ASSET, pot), 0);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+    });
+}
+
+#[test]
+fn cancel_match_refunds_bets_in_assets() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Betting::create_match_to_bet(
+            RuntimeOrigin::signed(1),
+            team("team1"),
+            team("team2"),
+            10,
+            10,
+            Permill::zero(),
+            Some(ASSET)
+        ));
+        let match_id = 0;
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            100,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::cancel_match(RuntimeOrigin::signed(1), match_id));
+        assert_eq!(Assets::balance(ASSET, 2), INIT_BALANCE);
+    });
+}
+
+#[test]
+fn creates_a_market_with_many_outcomes() {
+    new_test_ext().execute_with(|| {
+        let horses = horses(8);
+        assert_ok!(Betting::create_market(
+            RuntimeOrigin::signed(1),
+            horses.clone(),
+            10,
+            10,
+            Permill::zero(),
+            None
+        ));
+        assert_eq!(outcome_names(0), horses);
+
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), 0, 10, 5));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(3), 0, 10, 0));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(4), 0, 20, 7));
+        assert_eq!(Betting::get_matches(0).unwrap().total_on(7), 20);
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 0, 5));
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(RuntimeOrigin::signed(1), 0));
+
+        // The only bettor on the winning horse takes the whole pot
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 30);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 10);
+        assert_eq!(Balances::free_balance(4), INIT_BALANCE - 20);
+        assert_eq!(Balances::free_balance(Betting::pot_account(0)), 0);
+    });
+}
+
+#[test]
+fn error_creating_a_market() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Betting::create_market(
+                RuntimeOrigin::signed(1),
+                vec![b"horse1".to_vec()],
+                10,
+                10,
+                Permill::zero(),
+                None
+            ),
+            Error::<Test>::TooFewOutcomes
+        );
+        assert_noop!(
+            Betting::create_market(
+                RuntimeOrigin::signed(1),
+                (0..9).map(|horse| vec![horse]).collect(),
+                10,
+                10,
+                Permill::zero(),
+                None
+            ),
+            Error::<Test>::TooManyOutcomes
+        );
+    });
+}
+
+#[test]
+fn error_unknown_outcome() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::bet(RuntimeOrigin::signed(2), match_id, 10, 3),
+            Error::<Test>::UnknownOutcome
+        );
+        System::set_block_number(22);
+        assert_noop!(
+            Betting::set_result(RawOrigin::Root.into(), match_id, 3),
+            Error::<Test>::UnknownOutcome
+        );
+    });
+}
+
+#[test]
+fn derives_markets_from_a_match() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        let total_id = derive_market(1, match_id, ScoreMarket::Total(10));
+        let handicap_id = derive_market(1, match_id, ScoreMarket::Handicap(-2));
+        let exact_id = derive_market(1, match_id, ScoreMarket::ExactScore(1));
+
+        assert_eq!(
+            Betting::get_derived_markets(match_id).to_vec(),
+            vec![total_id, handicap_id, exact_id]
+        );
+        assert_eq!(outcome_names(total_id), vec![OVER.to_vec(), UNDER.to_vec()]);
+        assert_eq!(
+            outcome_names(handicap_id),
+            vec![b"team1".to_vec(), b"team2".to_vec()]
+        );
+        assert_eq!(
+            outcome_names(exact_id),
+            vec![
+                b"0-0".to_vec(),
+                b"0-1".to_vec(),
+                b"1-0".to_vec(),
+                b"1-1".to_vec(),
+                OTHER_SCORE.to_vec()
+            ]
+        );
+        // Every derived market has its own deposit and pot
+        assert_eq!(Balances::reserved_balance(1), 40);
+        System::assert_last_event(RuntimeEvent::Betting(Event::MarketDerived(
+            match_id,
+            exact_id,
+            ScoreMarket::ExactScore(1),
+        )));
+    });
+}
+
+#[test]
+fn error_deriving_a_market() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        let total_id = derive_market(1, match_id, ScoreMarket::Total(10));
+        assert_noop!(
+            Betting::create_derived_market(
+                RuntimeOrigin::signed(2),
+                match_id,
+                ScoreMarket::Total(6),
+                Permill::zero()
+            ),
+            Error::<Test>::NotMatchCreator
+        );
+        assert_noop!(
+            Betting::create_derived_market(
+                RuntimeOrigin::signed(1),
+                match_id,
+                ScoreMarket::Total(10),
+                Permill::zero()
+            ),
+            Error::<Test>::MatchAlreadyExists
+        );
+        assert_noop!(
+            Betting::create_derived_market(
+                RuntimeOrigin::signed(1),
+                total_id,
+                ScoreMarket::Total(6),
+                Permill::zero()
+            ),
+            Error::<Test>::NotTwoTeamMatch
+        );
+        // 3 goals per team make 17 outcomes
+        assert_noop!(
+            Betting::create_derived_market(
+                RuntimeOrigin::signed(1),
+                match_id,
+                ScoreMarket::ExactScore(3),
+                Permill::zero()
+            ),
+            Error::<Test>::TooManyOutcomes
+        );
+        for line in [2, 6, 14] {
+            derive_market(1, match_id, ScoreMarket::Total(line));
+        }
+        assert_noop!(
+            Betting::create_derived_market(
+                RuntimeOrigin::signed(1),
+                match_id,
+                ScoreMarket::Total(18),
+                Permill::zero()
+            ),
+            Error::<Test>::TooManyDerivedMarkets
+        );
+    });
+}
+
+#[test]
+fn set_score_settles_derived_markets() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        let total_id = derive_market(1, match_id, ScoreMarket::Total(10));
+        let exact_id = derive_market(1, match_id, ScoreMarket::ExactScore(1));
+
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), total_id, 10, 0));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(3), total_id, 30, 1));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(4), exact_id, 10, 4));
+
+        System::set_block_number(22);
+        let score = Score { team1: 2, team2: 1 };
+        assert_ok!(Betting::set_score(RawOrigin::Root.into(), match_id, score));
+        assert_eq!(
+            Betting::get_matches(match_id).unwrap().result,
+            Some(MatchResult::Team1Victory.index())
+        );
+        // Three goals are over 2.5, and 2-1 is another score than the ones listed
+        assert_eq!(Betting::get_matches(total_id).unwrap().result, Some(0));
+        assert_eq!(Betting::get_matches(exact_id).unwrap().result, Some(4));
+        System::assert_has_event(RuntimeEvent::Betting(Event::ScoreSet(total_id, score)));
+
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            total_id
+        ));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 30);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 30);
+        assert!(!DerivedMarkets::<Test>::get(match_id).contains(&total_id));
+    });
+}
+
+#[test]
+fn quarter_handicap_half_wins() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        // Team 1 gives a quarter of a goal
+        let handicap_id = derive_market(1, match_id, ScoreMarket::Handicap(-1));
+
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), handicap_id, 40, 0));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(3), handicap_id, 20, 1));
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_score(
+            RawOrigin::Root.into(),
+            match_id,
+            Score { team1: 1, team2: 1 }
+        ));
+        assert_eq!(
+            Betting::get_matches(handicap_id).unwrap().settlement(),
+            Some(Settlement::HalfWin(1))
+        );
+
+        System::set_block_number(27);
+        // The bettor on team 1 gets half of the bet back
+        assert_ok!(Betting::claim_winnings(
+            RuntimeOrigin::signed(2),
+            handicap_id
+        ));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 20);
+        // The bettor on team 2 gets half of the bet back and wins the other half of the pot
+        assert_ok!(Betting::claim_winnings(
+            RuntimeOrigin::signed(3),
+            handicap_id
+        ));
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE + 20);
+        assert_eq!(Betting::get_matches(handicap_id), None);
+    });
+}
+
+#[test]
+fn half_win_without_winners_refunds_bets() {
+    new_test_ext().execute_with(|| {
+        let handicap_id = create_half_win_without_winners();
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            handicap_id
+        ));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE);
+        System::assert_has_event(RuntimeEvent::Betting(Event::PotRefunded(handicap_id, 62)));
+    });
+}
+
+#[test]
+fn half_win_without_winners_rolls_stakes_over() {
+    new_test_ext().execute_with(|| {
+        NoWinner::set(NoWinnerPolicy::Rollover);
+        let handicap_id = create_half_win_without_winners();
+        assert_ok!(Betting::claim_winnings(
+            RuntimeOrigin::signed(2),
+            handicap_id
+        ));
+        // Only the half of each bet at stake is rolled over
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 20);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 10);
+        assert_eq!(Betting::get_rollover(1, None), Some((handicap_id, 30)));
+        System::assert_has_event(RuntimeEvent::Betting(Event::PotRolledOver(
+            handicap_id,
+            1,
+            30,
+        )));
+    });
+}
+
+#[test]
+fn half_win_without_winners_sends_stakes_to_treasury() {
+    new_test_ext().execute_with(|| {
+        NoWinner::set(NoWinnerPolicy::Treasury);
+        let handicap_id = create_half_win_without_winners();
+        let issuance_before = Balances::total_issuance();
+        System::set_block_number(47);
+        assert_ok!(Betting::reap_match(RuntimeOrigin::signed(4), handicap_id));
+        // Only the half of each bet at stake goes to the treasury
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 20);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 10);
+        assert_eq!(Balances::total_issuance(), issuance_before - 30);
+        System::assert_has_event(RuntimeEvent::Betting(Event::PotSentToTreasury(
+            handicap_id,
+            30,
+        )));
+    });
+}
+
+#[test]
+fn whole_line_push_refunds_every_bet() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        let total_id = derive_market(1, match_id, ScoreMarket::Total(8));
+
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), total_id, 10, 0));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(3), total_id, 30, 1));
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_score(
+            RawOrigin::Root.into(),
+            match_id,
+            Score { team1: 1, team2: 1 }
+        ));
+        let total_market = Betting::get_matches(total_id).unwrap();
+        assert_eq!(total_market.result, None);
+        assert_eq!(total_market.settlement(), Some(Settlement::Push));
+
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            total_id
+        ));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE);
+        System::assert_has_event(RuntimeEvent::Betting(Event::PotRefunded(total_id, 40)));
+    });
+}
+
+#[test]
+fn error_set_score() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        let score = Score { team1: 0, team2: 0 };
+        assert_noop!(
+            Betting::set_score(RuntimeOrigin::signed(1), match_id, score),
+            BadOrigin
+        );
+        assert_noop!(
+            Betting::set_score(RawOrigin::Root.into(), match_id, score),
+            Error::<Test>::TimeMatchNotOver
+        );
+        assert_ok!(Betting::create_market(
+            RuntimeOrigin::signed(1),
+            horses(2),
+            10,
+            10,
+            Permill::zero(),
+            None
+        ));
+
+        System::set_block_number(22);
+        assert_noop!(
+            Betting::set_score(RawOrigin::Root.into(), 1, score),
+            Error::<Test>::NotScoreMarket
+        );
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Draw.index()
+        ));
+        assert_noop!(
+            Betting::set_score(RawOrigin::Root.into(), match_id, score),
+            Error::<Test>::ResultAlreadySet
+        );
+    });
+}
+
+#[test]
+fn fixed_odds_pays_winners_from_the_bond() {
+    new_test_ext().execute_with(|| {
+        let odds = vec![
+            FixedU128::saturating_from_rational(5, 2),
+            FixedU128::saturating_from_integer(3),
+        ];
+        let match_id = create_fixed_odds_market(1, odds, 100);
+        System::assert_has_event(RuntimeEvent::Betting(Event::OddsPosted(match_id, 100)));
+        assert_eq!(Balances::reserved_balance(1), 10 + 100);
+
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), match_id, 30, 0));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(3), match_id, 10, 0));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(4), match_id, 20, 1));
+        // 70 at 2.5 would win 105, more than the bond
+        assert_noop!(
+            Betting::bet(RuntimeOrigin::signed(2), match_id, 30, 0),
+            Error::<Test>::InsufficientBond
+        );
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), match_id, 0));
+        System::set_block_number(27);
+
+        // A winner is paid its stake times the odds
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+        System::assert_has_event(RuntimeEvent::Betting(Event::WinningsClaimed(
+            match_id, 2, 75,
+        )));
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 45);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE + 15);
+        assert_eq!(Balances::free_balance(4), INIT_BALANCE - 20);
+        // The creator keeps the losing bets and gets the rest of the bond back
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE + 20 - 60);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Betting::get_book(match_id), None);
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 0);
+    });
+}
+
+#[test]
+fn cancelling_a_fixed_odds_market_releases_the_bond() {
+    new_test_ext().execute_with(|| {
+        let odds = vec![FixedU128::saturating_from_integer(2); 2];
+        let match_id = create_fixed_odds_market(1, odds, 100);
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), match_id, 50, 1));
+
+        assert_ok!(Betting::cancel_match(RuntimeOrigin::signed(1), match_id));
+
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+        assert_eq!(Betting::get_book(match_id), None);
+    });
+}
+
+#[test]
+fn error_creating_a_fixed_odds_market() {
+    new_test_ext().execute_with(|| {
+        let horses = horses(2);
+        assert_noop!(
+            Betting::create_fixed_odds_market(
+                RuntimeOrigin::signed(1),
+                horses.clone(),
+                vec![FixedU128::saturating_from_integer(2)],
+                100,
+                10,
+                10
+            ),
+            Error::<Test>::OddsMismatch
+        );
+        assert_noop!(
+            Betting::create_fixed_odds_market(
+                RuntimeOrigin::signed(1),
+                horses,
+                vec![FixedU128::saturating_from_integer(2), FixedU128::one()],
+                100,
+                10,
+                10
+            ),
+            Error::<Test>::InvalidOdds
+        );
+    });
+}
+
+#[test]
+fn market_maker_prices_add_up() {
+    let liquidity = lmsr::liquidity(1_000, 3);
+    assert_eq!(liquidity, 910);
+
+    // Buying as many shares of every outcome costs that many units, up to the rounding
+    let mut shares = vec![0, 0, 0];
+    let mut cost = 0;
+    for outcome in 0..3 {
+        cost += lmsr::buy_cost(&shares, liquidity, outcome, 1_000).unwrap();
+        shares[outcome] += 1_000;
+    }
+    assert!((1_000..=1_003).contains(&cost));
+
+    // And they are worth as many units at the quoted prices
+    let value: u128 = (0..3)
+        .map(|outcome| lmsr::value(&shares, liquidity, outcome, 1_000).unwrap())
+        .sum();
+    assert!((997..=1_000).contains(&value));
+}
+
+#[test]
+fn market_maker_values_shares_far_from_the_quoted_price() {
+    // The exponent of the outcome with no shares overflows, its price is zero
+    let shares = [u128::MAX / 2, 0];
+    assert_eq!(lmsr::value(&shares, 1, 1, 10), Some(0));
+    assert_eq!(lmsr::value(&shares, 1, 0, 10), Some(10));
+    assert_eq!(lmsr::value(&shares, 0, 0, 10), None);
+}
+
+#[test]
+fn market_maker_quotes_and_redeems_shares() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_amm_market(1, 3, 1_000);
+        System::assert_has_event(RuntimeEvent::Betting(Event::SubsidyReserved(
+            match_id, 1_000,
+        )));
+        assert_eq!(Balances::reserved_balance(1), 10 + 1_000);
+
+        assert_eq!(Betting::quote_shares(match_id, 0, 100), Ok(35));
+        assert_ok!(Betting::buy_shares(
+            RuntimeOrigin::signed(2),
+            match_id,
+            0,
+            100,
+            35
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::SharesBought(
+            match_id, 2, 0, 100, 35,
+        )));
+
+        // Buying the outcome moved its price up
+        assert_eq!(Betting::quote_shares(match_id, 0, 100), Ok(38));
+        assert_noop!(
+            Betting::buy_shares(RuntimeOrigin::signed(3), match_id, 0, 100, 37),
+            Error::<Test>::SlippageExceeded
+        );
+        assert_ok!(Betting::buy_shares(
+            RuntimeOrigin::signed(3),
+            match_id,
+            1,
+            100,
+            34
+        ));
+
+        assert_noop!(
+            Betting::sell_shares(RuntimeOrigin::signed(2), match_id, 0, 50, 16),
+            Error::<Test>::SlippageExceeded
+        );
+        assert_ok!(Betting::sell_shares(
+            RuntimeOrigin::signed(2),
+            match_id,
+            0,
+            50,
+            15
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::SharesSold(
+            match_id, 2, 0, 50, 15,
+        )));
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), match_id, 0));
+        System::set_block_number(27);
+
+        // Every share of the winning outcome is redeemed for one unit
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+        System::assert_has_event(RuntimeEvent::Betting(Event::WinningsClaimed(
+            match_id, 2, 50,
+        )));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 35 + 15 + 50);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 34);
+
+        // The creator keeps what the market maker made and gets the subsidy back
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE + 35 + 34 - 15 - 50);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Betting::get_matches(match_id), None);
+        assert_eq!(Betting::get_market_maker(match_id), None);
+        assert_eq!(Balances::free_balance(Betting::pot_account(match_id)), 0);
+    });
+}
+
+#[test]
+fn cancelling_a_market_maker_redeems_shares_at_its_prices() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_amm_market(1, 3, 1_000);
+        assert_ok!(Betting::buy_shares(
+            RuntimeOrigin::signed(2),
+            match_id,
+            0,
+            100,
+            35
+        ));
+        assert_ok!(Betting::buy_shares(
+            RuntimeOrigin::signed(3),
+            match_id,
+            1,
+            100,
+            34
+        ));
+
+        assert_ok!(Betting::cancel_match(RuntimeOrigin::signed(1), match_id));
+
+        // Both outcomes are quoted at 0.345 once both have been bought
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 35 + 34);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 34 + 34);
+        assert_eq!(Balances::free_balance(1), INIT_BALANCE + 35 + 34 - 68);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Betting::get_market_maker(match_id), None);
+    });
+}
+
+#[test]
+fn error_trading_shares() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Betting::create_amm_market(RuntimeOrigin::signed(1), horses(2), 0, 10, 10),
+            Error::<Test>::SubsidyTooLow
+        );
+
+        let pool_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::buy_shares(RuntimeOrigin::signed(2), pool_id, 0, 100, 100),
+            Error::<Test>::NoMarketMaker
+        );
+
+        let match_id = create_amm_market(1, 3, 1_000);
+        assert_noop!(
+            Betting::bet(RuntimeOrigin::signed(2), match_id, 100, 0),
+            Error::<Test>::MarketMakerMatch
+        );
+        assert_noop!(
+            Betting::buy_shares(RuntimeOrigin::signed(2), match_id, 3, 100, 100),
+            Error::<Test>::UnknownOutcome
+        );
+        assert_noop!(
+            Betting::buy_shares(RuntimeOrigin::signed(2), match_id, 0, 0, 100),
+            Error::<Test>::ZeroAmount
+        );
+        assert_noop!(
+            Betting::sell_shares(RuntimeOrigin::signed(2), match_id, 0, 100, 0),
+            Error::<Test>::NoBet
+        );
+
+        assert_ok!(Betting::buy_shares(
+            RuntimeOrigin::signed(2),
+            match_id,
+            0,
+            100,
+            35
+        ));
+        assert_noop!(
+            Betting::cancel_bet(RuntimeOrigin::signed(2), match_id, 0),
+            Error::<Test>::MarketMakerMatch
+        );
+        assert_noop!(
+            Betting::sell_shares(RuntimeOrigin::signed(2), match_id, 0, 101, 0),
+            Error::<Test>::AmountExceedsBet
+        );
+
+        System::set_block_number(10);
+        assert_noop!(
+            Betting::sell_shares(RuntimeOrigin::signed(2), match_id, 0, 100, 0),
+            Error::<Test>::MatchHasStarted
+        );
+    });
+}
+
+#[test]
+fn bets_in_play_share_the_winnings_for_the_part_of_the_match_left() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::open_in_play(RuntimeOrigin::signed(1), match_id));
+        System::assert_has_event(RuntimeEvent::Betting(Event::InPlayOpened(match_id)));
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(2),
+            match_id,
+            10,
+            MatchResult::Team1Victory.index()
+        ));
+
+        // A bet placed as the match starts is not discounted
+        System::set_block_number(10);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(4),
+            match_id,
+            10,
+            MatchResult::Team2Victory.index()
+        ));
+        assert_eq!(
+            Betting::get_in_play_discount((match_id, 4, MatchResult::Team2Victory.index())),
+            0
+        );
+
+        // Half of a bet placed halfway through the match is discounted
+        System::set_block_number(15);
+        assert_ok!(Betting::bet(
+            RuntimeOrigin::signed(3),
+            match_id,
+            20,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_eq!(
+            Betting::get_in_play_discount((match_id, 3, MatchResult::Team1Victory.index())),
+            10
+        );
+        assert_eq!(
+            Betting::get_in_play(match_id).unwrap().discounts.to_vec(),
+            vec![10, 0, 0]
+        );
+
+        System::set_block_number(20);
+        assert_noop!(
+            Betting::bet(
+                RuntimeOrigin::signed(2),
+                match_id,
+                10,
+                MatchResult::Team1Victory.index()
+            ),
+            Error::<Test>::TimeMatchOver
+        );
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(27);
+
+        // The pot of 40 is shared by 10 bet before the match and 10 of the late bet
+        assert_ok!(Betting::claim_winnings(RuntimeOrigin::signed(2), match_id));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 10);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            match_id
+        ));
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE);
+        assert_eq!(Betting::get_in_play(match_id), None);
+        assert_eq!(
+            Betting::get_in_play_discount((match_id, 3, MatchResult::Team1Victory.index())),
+            0
+        );
+    });
+}
+
+#[test]
+fn oracle_suspends_betting_in_play() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::open_in_play(RuntimeOrigin::signed(1), match_id));
+
+        System::set_block_number(12);
+        assert_ok!(Betting::suspend_betting(
+            RuntimeOrigin::signed(ORACLE),
+            match_id,
+            3
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::BettingSuspended(match_id, 15)));
+        System::set_block_number(14);
+        assert_noop!(
+            Betting::bet(RuntimeOrigin::signed(2), match_id, 10, 0),
+            Error::<Test>::BettingSuspended
+        );
+
+        // The betting resumes once the suspension is over
+        System::set_block_number(15);
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), match_id, 10, 0));
+
+        // Or as soon as the oracle resumes it
+        assert_ok!(Betting::suspend_betting(
+            RawOrigin::Root.into(),
+            match_id,
+            3
+        ));
+        assert_noop!(
+            Betting::bet(RuntimeOrigin::signed(3), match_id, 10, 1),
+            Error::<Test>::BettingSuspended
+        );
+        assert_ok!(Betting::resume_betting(
+            RuntimeOrigin::signed(ORACLE),
+            match_id
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::BettingResumed(match_id)));
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(3), match_id, 10, 1));
+    });
+}
+
+#[test]
+fn market_maker_trades_in_play() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_amm_market(1, 3, 1_000);
+        assert_ok!(Betting::open_in_play(RuntimeOrigin::signed(1), match_id));
+
+        // Shares trade at the prices quoted while the match is played
+        System::set_block_number(15);
+        assert_ok!(Betting::buy_shares(
+            RuntimeOrigin::signed(2),
+            match_id,
+            0,
+            100,
+            35
+        ));
+        assert_ok!(Betting::suspend_betting(
+            RawOrigin::Root.into(),
+            match_id,
+            2
+        ));
+        assert_noop!(
+            Betting::sell_shares(RuntimeOrigin::signed(2), match_id, 0, 100, 0),
+            Error::<Test>::BettingSuspended
+        );
+    });
+}
+
+#[test]
+fn error_suspending_betting_once_the_match_is_over() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_ok!(Betting::open_in_play(RuntimeOrigin::signed(1), match_id));
+
+        System::set_block_number(20);
+        assert_noop!(
+            Betting::suspend_betting(RuntimeOrigin::signed(ORACLE), match_id, 3),
+            Error::<Test>::MatchNotInPlay
+        );
+
+        System::set_block_number(21);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            match_id,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_noop!(
+            Betting::suspend_betting(RuntimeOrigin::signed(ORACLE), match_id, 3),
+            Error::<Test>::MatchNotInPlay
+        );
+    });
+}
+
+#[test]
+fn error_betting_in_play() {
+    new_test_ext().execute_with(|| {
+        let match_id = create_match(1, "team1", "team2", 10, 10);
+        assert_noop!(
+            Betting::open_in_play(RuntimeOrigin::signed(2), match_id),
+            Error::<Test>::NotMatchCreator
+        );
+        assert_noop!(
+            Betting::suspend_betting(RuntimeOrigin::signed(ORACLE), match_id, 3),
+            Error::<Test>::NotInPlay
+        );
+        assert_noop!(
+            Betting::resume_betting(RuntimeOrigin::signed(ORACLE), match_id),
+            Error::<Test>::NotInPlay
+        );
+
+        assert_ok!(Betting::open_in_play(RuntimeOrigin::signed(1), match_id));
+        assert_noop!(
+            Betting::open_in_play(RuntimeOrigin::signed(1), match_id),
+            Error::<Test>::AlreadyInPlay
+        );
+        assert_noop!(
+            Betting::suspend_betting(RuntimeOrigin::signed(2), match_id, 3),
+            BadOrigin
+        );
+        assert_noop!(
+            Betting::suspend_betting(RuntimeOrigin::signed(ORACLE), match_id, 4),
+            Error::<Test>::SuspensionTooLong
+        );
+
+        let odds_id = create_fixed_odds_market(
+            1,
+            vec![
+                FixedU128::saturating_from_integer(2),
+                FixedU128::saturating_from_integer(3),
+            ],
+            100,
+        );
+        assert_noop!(
+            Betting::open_in_play(RuntimeOrigin::signed(1), odds_id),
+            Error::<Test>::FixedOddsInPlay
+        );
+
+        // Bets can still be cancelled only before the match starts
+        System::set_block_number(10);
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), match_id, 10, 0));
+        assert_noop!(
+            Betting::cancel_bet(RuntimeOrigin::signed(2), match_id, 0),
+            Error::<Test>::MatchHasStarted
+        );
+
+        let late_id = create_match(1, "team3", "team4", 20, 10);
+        System::set_block_number(20);
+        assert_noop!(
+            Betting::open_in_play(RuntimeOrigin::signed(1), late_id),
+            Error::<Test>::MatchHasStarted
+        );
+    });
+}
+
+#[test]
+fn parlay_pays_at_the_combined_odds() {
+    new_test_ext().execute_with(|| {
+        let first_id = create_fixed_odds_market(1, odds(&[2, 3]), 100);
+        let mut second_odds = odds(&[4, 4, 4]);
+        second_odds[0] = FixedU128::saturating_from_rational(3, 2);
+        let second_id = create_fixed_odds_market(1, second_odds, 100);
+
+        // Winning both legs pays three times the stake, the winnings reserved from the bookmaker
+        assert_ok!(Betting::place_parlay(
+            RuntimeOrigin::signed(2),
+            vec![(first_id, 0), (second_id, 0)],
+            10
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::ParlayPlaced(0, 2, 10, 20)));
+        assert_eq!(Balances::reserved_balance(2), 10);
+        assert_eq!(Balances::reserved_balance(1), 10 + 10 + 200 + 20);
+        assert_eq!(Betting::get_book(first_id).unwrap().bond, 100);
+        assert_eq!(Betting::get_match_parlays(second_id).to_vec(), vec![0]);
+
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), first_id, 0));
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), second_id, 0));
+        assert_noop!(
+            Betting::settle_parlay(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::ParlayNotSettled
+        );
+        System::set_block_number(27);
+
+        assert_ok!(Betting::settle_parlay(RuntimeOrigin::signed(3), 0));
+        System::assert_has_event(RuntimeEvent::Betting(Event::ParlayWon(0, 2, 20)));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 20);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Betting::get_parlay(0), None);
+        assert!(Betting::get_match_parlays(second_id).is_empty());
+
+        // The bonds are released with the matches
+        assert_eq!(Balances::reserved_balance(1), 10 + 10 + 200);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            first_id
+        ));
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            second_id
+        ));
+        assert_eq!(Balances::reserved_balance(1), 0);
+    });
+}
+
+#[test]
+fn parlay_drops_void_legs_and_loses_on_a_losing_leg() {
+    new_test_ext().execute_with(|| {
+        let first_id = create_fixed_odds_market(1, odds(&[2, 3]), 100);
+        let mut second_odds = odds(&[4, 4, 4]);
+        second_odds[0] = FixedU128::saturating_from_rational(3, 2);
+        let second_id = create_fixed_odds_market(1, second_odds, 100);
+        let third_id = create_fixed_odds_market(1, odds(&[2, 2, 2, 2]), 100);
+
+        assert_ok!(Betting::place_parlay(
+            RuntimeOrigin::signed(2),
+            vec![(first_id, 0), (second_id, 0)],
+            10
+        ));
+        assert_ok!(Betting::place_parlay(
+            RuntimeOrigin::signed(3),
+            vec![(first_id, 1), (third_id, 0)],
+            10
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::ParlayPlaced(1, 3, 10, 50)));
+        assert_eq!(Betting::get_book(first_id).unwrap().bond, 100);
+
+        // The leg on a cancelled match is dropped
+        assert_ok!(Betting::cancel_match(RuntimeOrigin::signed(1), second_id));
+        assert!(Betting::get_parlay(0).is_some());
+
+        // Settling the first match settles both parlays
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), first_id, 0));
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            first_id
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::ParlayWon(0, 2, 10)));
+        System::assert_has_event(RuntimeEvent::Betting(Event::ParlayLost(1, 3)));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE + 10);
+        assert_eq!(Balances::free_balance(3), INIT_BALANCE - 10);
+        assert_eq!(Betting::get_parlay(0), None);
+        assert_eq!(Betting::get_parlay(1), None);
+        assert!(Betting::get_match_parlays(third_id).is_empty());
+
+        // Only the deposit and the bond of the third match are still reserved
+        assert_eq!(Balances::reserved_balance(1), 10 + 100);
+    });
+}
+
+#[test]
+fn parlay_lost_early_releases_its_liability() {
+    new_test_ext().execute_with(|| {
+        let first_id = create_fixed_odds_market(1, odds(&[2, 3]), 100);
+        let second_id = create_fixed_odds_market(1, odds(&[2, 2, 2]), 100);
+        assert_ok!(Betting::place_parlay(
+            RuntimeOrigin::signed(2),
+            vec![(first_id, 0), (second_id, 0)],
+            10
+        ));
+
+        // The second leg loses before the match of the first leg is settled
+        System::set_block_number(22);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), second_id, 1));
+        System::set_block_number(27);
+        assert_ok!(Betting::distribute_winnings(
+            RuntimeOrigin::signed(1),
+            second_id
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::ParlayLost(0, 2)));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE - 10);
+
+        // Only the deposit and the whole bond of the first match are still reserved
+        assert_eq!(Balances::reserved_balance(1), 10 + 100);
+        assert_eq!(Betting::get_book(first_id).unwrap().bond, 100);
+    });
+}
+
+#[test]
+fn error_placing_a_parlay() {
+    new_test_ext().execute_with(|| {
+        let first_id = create_fixed_odds_market(1, odds(&[2, 3]), 100);
+        let second_id = create_fixed_odds_market(1, odds(&[2, 2, 2]), 100);
+        let other_id = create_fixed_odds_market(2, odds(&[2, 2, 2, 2]), 100);
+        let pool_id = create_match(1, "team1", "team2", 10, 10);
+
+        let place = |legs: Vec<(MatchId, OutcomeIndex)>, stake| {
+            Betting::place_parlay(RuntimeOrigin::signed(3), legs, stake)
+        };
+        assert_noop!(place(vec![(first_id, 0)], 10), Error::<Test>::TooFewLegs);
+        assert_noop!(
+            place(vec![(first_id, 0); 5], 10),
+            Error::<Test>::TooManyLegs
+        );
+        assert_noop!(
+            place(vec![(first_id, 0), (first_id, 1)], 10),
+            Error::<Test>::DuplicateLeg
+        );
+        assert_noop!(
+            place(vec![(first_id, 0), (99, 0)], 10),
+            Error::<Test>::MatchDoesNotExist
+        );
+        assert_noop!(
+            place(vec![(first_id, 0), (pool_id, 0)], 10),
+            Error::<Test>::NotFixedOdds
+        );
+        assert_noop!(
+            place(vec![(first_id, 2), (second_id, 0)], 10),
+            Error::<Test>::UnknownOutcome
+        );
+        assert_noop!(
+            place(vec![(first_id, 0), (other_id, 0)], 10),
+            Error::<Test>::MixedBookmakers
+        );
+        assert_noop!(
+            place(vec![(first_id, 0), (second_id, 0)], INIT_BALANCE / 2),
+            Error::<Test>::InsufficientBond
+        );
+
+        for _ in 0..3 {
+            assert_ok!(place(vec![(first_id, 0), (second_id, 0)], 10));
+        }
+        assert_noop!(
+            place(vec![(second_id, 0), (first_id, 0)], 10),
+            Error::<Test>::TooManyParlays
+        );
+
+        assert_noop!(
+            Betting::settle_parlay(RuntimeOrigin::signed(3), 99),
+            Error::<Test>::ParlayDoesNotExist
+        );
+        System::set_block_number(10);
+        assert_noop!(
+            place(vec![(first_id, 1), (other_id, 0)], 10),
+            Error::<Test>::MatchHasStarted
+        );
+    });
+}
+
+#[test]
+fn knockout_advances_the_winners_and_settles_the_outright() {
+    new_test_ext().execute_with(|| {
+        let tournament_id = create_tournament(TournamentFormat::Knockout, &["A", "B", "C", "D"]);
+        System::assert_has_event(RuntimeEvent::Betting(Event::TournamentCreated(
+            tournament_id,
+            1,
+            0,
+        )));
+        let outright = Betting::get_matches(0).unwrap();
+        assert_eq!(
+            outcome_names(0),
+            vec![b"A".to_vec(), b"B".to_vec(), b"C".to_vec(), b"D".to_vec()]
+        );
+        assert_eq!((outright.start, outright.length), (10, 40));
+        assert_eq!(fixtures(tournament_id), vec![(1, 0, 1), (2, 2, 3)]);
+        assert_eq!(outcome_names(2), vec![b"C".to_vec(), b"D".to_vec()]);
+        assert_ok!(Betting::bet(RuntimeOrigin::signed(2), 0, 10, 3));
+
+        System::set_block_number(21);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 1, 0));
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 2, 1));
+        assert_noop!(
+            Betting::advance_tournament(RuntimeOrigin::signed(3), tournament_id),
+            Error::<Test>::RoundNotOver
+        );
+
+        // The winners play the final in the next round
+        System::set_block_number(26);
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::RoundScheduled(
+            tournament_id,
+            1,
+        )));
+        assert_eq!(fixtures(tournament_id), vec![(3, 0, 3)]);
+        assert_eq!(outcome_names(3), vec![b"A".to_vec(), b"D".to_vec()]);
+        assert_eq!(Betting::get_matches(3).unwrap().start, 30);
+
+        // The semi-finals can be closed while the final is played
+        assert_ok!(Betting::distribute_winnings(RuntimeOrigin::signed(1), 1));
+        assert_eq!(Betting::get_match_tournament(1), None);
+
+        System::set_block_number(41);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 3, 1));
+        System::set_block_number(46);
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchResult(0, 3)));
+        System::assert_last_event(RuntimeEvent::Betting(Event::TournamentFinished(
+            tournament_id,
+            Some(3),
+        )));
+        assert_eq!(Betting::get_tournament(tournament_id), None);
+        assert_eq!(Betting::get_match_tournament(3), None);
+
+        // The outright market pays once its result is final
+        System::set_block_number(51);
+        assert_ok!(Betting::distribute_winnings(RuntimeOrigin::signed(1), 0));
+        assert_eq!(Balances::free_balance(2), INIT_BALANCE);
+    });
+}
+
+#[test]
+fn on_idle_advances_tournaments_once_their_round_is_settled() {
+    new_test_ext().execute_with(|| {
+        let tournament_id = create_tournament(TournamentFormat::Knockout, &["A", "B", "C", "D"]);
+        System::set_block_number(21);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 1, 0));
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 2, 1));
+
+        // The semi-finals are settled and the final is scheduled in the same block
+        System::set_block_number(26);
+        Betting::on_idle(26, Weight::MAX);
+        assert_eq!(Betting::get_matches(1), None);
+        assert_eq!(Betting::get_matches(2), None);
+        System::assert_has_event(RuntimeEvent::Betting(Event::RoundScheduled(
+            tournament_id,
+            1,
+        )));
+        assert_eq!(fixtures(tournament_id), vec![(3, 0, 3)]);
+
+        System::set_block_number(41);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 3, 1));
+        System::set_block_number(46);
+        Betting::on_idle(46, Weight::MAX);
+        System::assert_has_event(RuntimeEvent::Betting(Event::TournamentFinished(
+            tournament_id,
+            Some(3),
+        )));
+        assert_eq!(Betting::get_tournament(tournament_id), None);
+        assert_eq!(Betting::get_matches(0).unwrap().result, Some(3));
+    });
+}
+
+#[test]
+fn knockout_replays_a_void_match() {
+    new_test_ext().execute_with(|| {
+        let tournament_id = create_tournament(TournamentFormat::Knockout, &["A", "B"]);
+        assert_ok!(Betting::cancel_match(RuntimeOrigin::signed(1), 1));
+        assert_eq!(Betting::get_match_tournament(1), None);
+
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        // The match is replayed in the same round
+        System::assert_has_event(RuntimeEvent::Betting(Event::RoundScheduled(
+            tournament_id,
+            0,
+        )));
+        assert_eq!(fixtures(tournament_id), vec![(2, 0, 1)]);
+        assert_eq!(Betting::get_matches(2).unwrap().start, 10);
+        assert_eq!(Betting::get_tournament(tournament_id).unwrap().rounds, 1);
+
+        System::set_block_number(21);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 2, 0));
+        System::set_block_number(26);
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_last_event(RuntimeEvent::Betting(Event::TournamentFinished(
+            tournament_id,
+            Some(0),
+        )));
+        assert_eq!(Betting::get_matches(0).unwrap().result, Some(0));
+    });
+}
+
+#[test]
+fn tournament_fixtures_do_not_clash_with_other_matches() {
+    new_test_ext().execute_with(|| {
+        // A match between the same teams at the same time as the first fixture
+        let match_id = create_match(2, "A", "B", 10, 10);
+        let tournament_id = create_tournament(TournamentFormat::Knockout, &["A", "B"]);
+        let (fixture_id, _, _) = fixtures(tournament_id)[0];
+        assert_eq!(Betting::get_matches(fixture_id).unwrap().start, 10);
+
+        // Deleting the fixture keeps the hash of the other match
+        assert_ok!(Betting::cancel_match(RuntimeOrigin::signed(1), fixture_id));
+        let mut hashed: Vec<_> = MatchHashes::<Test>::iter_values().collect();
+        hashed.sort();
+        assert_eq!(hashed, vec![match_id, 1]);
+        assert_noop!(
+            Betting::create_match_to_bet(
+                RuntimeOrigin::signed(3),
+                team("A"),
+                team("B"),
+                10,
+                10,
+                Permill::zero(),
+                None
+            ),
+            Error::<Test>::MatchAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn knockout_pairs_the_winners_of_a_replayed_round_in_bracket_order() {
+    new_test_ext().execute_with(|| {
+        let tournament_id = create_tournament(TournamentFormat::Knockout, &["A", "B", "C", "D"]);
+
+        // The first semi-final is voided and D wins the second one
+        System::set_block_number(21);
+        assert_ok!(Betting::void_match(RawOrigin::Root.into(), 1));
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            2,
+            MatchResult::Team2Victory.index()
+        ));
+        System::set_block_number(26);
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_last_event(RuntimeEvent::Betting(Event::RoundScheduled(
+            tournament_id,
+            0,
+        )));
+        assert_eq!(fixtures(tournament_id), vec![(3, 0, 1), (2, 2, 3)]);
+        assert_eq!(Betting::get_matches(3).unwrap().start, 27);
+
+        // A wins the replay and plays D in the final of the second round
+        System::set_block_number(38);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            3,
+            MatchResult::Team1Victory.index()
+        ));
+        System::set_block_number(43);
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_last_event(RuntimeEvent::Betting(Event::RoundScheduled(
+            tournament_id,
+            1,
+        )));
+        assert_eq!(fixtures(tournament_id), vec![(4, 0, 3)]);
+        assert_eq!(outcome_names(4), vec![b"A".to_vec(), b"D".to_vec()]);
+        assert_eq!(Betting::get_tournament(tournament_id).unwrap().rounds, 2);
+
+        System::set_block_number(55);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            4,
+            MatchResult::Team2Victory.index()
+        ));
+        System::set_block_number(60);
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_last_event(RuntimeEvent::Betting(Event::TournamentFinished(
+            tournament_id,
+            Some(3),
+        )));
+        assert_eq!(Betting::get_matches(0).unwrap().result, Some(3));
+    });
+}
+
+#[test]
+fn league_settles_the_outright_on_the_leader() {
+    new_test_ext().execute_with(|| {
+        // Every team sits one of the three rounds out
+        let tournament_id = create_tournament(TournamentFormat::League, &["A", "B", "C"]);
+        assert_eq!(
+            fixtures(tournament_id),
+            vec![(1, 1, 2), (2, 0, 1), (3, 0, 2)]
+        );
+        assert_eq!(
+            outcome_names(1),
+            vec![b"B".to_vec(), b"C".to_vec(), DRAW.to_vec()]
+        );
+        let starts: Vec<_> = (1..=3)
+            .map(|id| Betting::get_matches(id).unwrap().start)
+            .collect();
+        assert_eq!(starts, vec![10, 30, 50]);
+        assert_eq!(Betting::get_matches(0).unwrap().length, 60);
+
+        System::set_block_number(61);
+        assert_ok!(Betting::set_score(
+            RawOrigin::Root.into(),
+            1,
+            Score { team1: 1, team2: 1 }
+        ));
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            2,
+            MatchResult::Team1Victory.index()
+        ));
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            3,
+            MatchResult::Team2Victory.index()
+        ));
+        System::set_block_number(66);
+
+        // C has four points, A three and B one
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchResult(0, 2)));
+        System::assert_last_event(RuntimeEvent::Betting(Event::TournamentFinished(
+            tournament_id,
+            Some(2),
+        )));
+    });
+}
+
+#[test]
+fn league_tie_voids_the_outright() {
+    new_test_ext().execute_with(|| {
+        let tournament_id = create_tournament(TournamentFormat::League, &["A", "B"]);
+        System::set_block_number(21);
+        assert_ok!(Betting::set_result(
+            RawOrigin::Root.into(),
+            1,
+            MatchResult::Draw.index()
+        ));
+        System::set_block_number(26);
+
+        assert_ok!(Betting::advance_tournament(
+            RuntimeOrigin::signed(3),
+            tournament_id
+        ));
+        System::assert_has_event(RuntimeEvent::Betting(Event::MatchVoided(0)));
+        System::assert_last_event(RuntimeEvent::Betting(Event::TournamentFinished(
+            tournament_id,
+            None,
+        )));
+        assert!(Betting::get_matches(0).unwrap().voided);
+    });
+}
+
+#[test]
+fn error_creating_a_tournament() {
+    new_test_ext().execute_with(|| {
+        let teams: Vec<_> = ["A", "B", "C", "D", "E", "F", "G", "H", "I"]
+            .iter()
+            .map(|name| team(name))
+            .collect();
+        let create = |format, teams: &[TeamId], match_length| {
+            Betting::create_tournament(
+                RuntimeOrigin::signed(1),
+                format,
+                teams.to_vec(),
+                10,
+                20,
+                match_length,
+                Permill::zero(),
+            )
+        };
+        assert_noop!(
+            create(TournamentFormat::League, &teams[..2], 21),
+            Error::<Test>::RoundTooShort
+        );
+        assert_noop!(
+            create(TournamentFormat::League, &teams[..1], 10),
+            Error::<Test>::TooFewOutcomes
+        );
+        assert_noop!(
+            create(TournamentFormat::League, &[teams[0], teams[0]], 10),
+            Error::<Test>::DuplicateTeam
+        );
+        assert_noop!(
+            create(TournamentFormat::League, &[teams[0], 99], 10),
+            Error::<Test>::UnknownTeam
+        );
+        assert_noop!(
+            create(TournamentFormat::Knockout, &teams[..3], 10),
+            Error::<Test>::BracketNotPowerOfTwo
+        );
+        assert_noop!(
+            create(TournamentFormat::League, &teams[..8], 10),
+            Error::<Test>::TooManyMatches
+        );
+        assert_noop!(
+            create(TournamentFormat::Knockout, &teams, 10),
+            Error::<Test>::TooManyOutcomes
+        );
+    });
+}
+
+#[test]
+fn error_advancing_a_tournament() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Betting::advance_tournament(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::TournamentDoesNotExist
+        );
+
+        let tournament_id = create_tournament(TournamentFormat::Knockout, &["A", "B"]);
+        assert_noop!(
+            Betting::advance_tournament(RuntimeOrigin::signed(3), tournament_id),
+            Error::<Test>::RoundNotOver
+        );
+
+        // A disputed result is not final
+        System::set_block_number(21);
+        assert_ok!(Betting::set_result(RawOrigin::Root.into(), 1, 0));
+        assert_ok!(Betting::dispute_result(RuntimeOrigin::signed(2), 1));
+        System::set_block_number(26);
+        assert_noop!(
+            Betting::advance_tournament(RuntimeOrigin::signed(3), tournament_id),
+            Error::<Test>::RoundNotOver
+        );
+    });
+}
+
+#[test]
+fn migrates_bets_out_of_matches() {
+    new_test_ext().execute_with(|| {
+        use frame_support::traits::ReservableCurrency;
+        use migrations::v1::{MigrateToV1, OldBet, OldMatch};
+        use sp_runtime::traits::AccountIdConversion;
+
+        StorageVersion::new(0).put::<Betting>();
+        let old_bet = |bettor, amount, result| OldBet {
+            bettor,
+            amount,
+            result,
+        };
+        let old_match = OldMatch {
+            start: 10u64,
+            length: 10u64,
+            team1: TeamName::<Test>::try_from(b"team1".to_vec()).unwrap(),
+            team2: TeamName::<Test>::try_from(b"team2".to_vec()).unwrap(),
+            result: None,
+            bets: vec![
+                old_bet(2, 10u128, MatchResult::Team1Victory),
+                old_bet(2, 20, MatchResult::Team1Victory),
+                old_bet(3, 5, MatchResult::Draw),
+            ],
+            deposit: 10,
+        };
+        migrations::v1::Matches::<Test>::insert(1, old_match);
+        // The stakes were held by the pallet account and the deposit reserved from the creator
+        let old_pot: u64 = BettingPalletId::get().into_account_truncating();
+        Balances::make_free_balance_be(&old_pot, 35);
+        assert_ok!(Balances::reserve(&1, 10));
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageVersion::get::<Betting>(), 1);
+        // The match is keyed by its id
+        let migrated = migrations::v2::Matches::<Test>::get(0).unwrap();
+        assert_eq!(migrated.creator, 1);
+        assert_eq!(migrated.bet_count, 2);
+        assert_eq!(migrated.totals.get(MatchResult::Team1Victory), 30);
+        assert_eq!(migrated.totals.get(MatchResult::Draw), 5);
+        assert_eq!(Betting::next_match_id(), 1);
+        assert!(MatchesByCreator::<Test>::contains_key(1, 0));
+        assert_eq!(
+            MatchHashes::<Test>::iter_values().collect::<Vec<_>>(),
+            vec![0]
+        );
+        // The bets of the same bettor on the same result are merged
+        assert_eq!(
+            migrations::v2::Bets::<Test>::get((0, 2, MatchResult::Team1Victory)),
+            Some(Bet {
+                amount: 30,
+                claimed: false
+            })
+        );
+        assert!(migrations::v2::Bets::<Test>::contains_key((
+            0,
+            3,
+            MatchResult::Draw
+        )));
+        // The pot holds the stakes, seeded out of the deposit of the creator
+        assert_eq!(Balances::free_balance(Betting::pot_account(0)), 37);
+        assert_eq!(Balances::free_balance(old_pot), 0);
+        assert_eq!(migrated.deposit, 8);
+        assert_eq!(Balances::reserved_balance(1), 8);
+        // The match is refunded by `on_idle` if it never gets a result
+        assert!(SettlementQueue::<Test>::contains_key(50, 0));
+        assert_eq!(SettlementCursor::<Test>::get(), Some(50));
+    });
+}
+
+#[test]
+fn migrates_matches_to_outcomes() {
+    new_test_ext().execute_with(|| {
+        use migrations::{
+            v2::{MigrateToV2, OldMatch, ResultTotals},
+            v3::MigrateToV3,
+            v4::MigrateToV4,
+            v5::MigrateToV5,
+        };
+
+        StorageVersion::new(1).put::<Betting>();
+        let old_match = OldMatch {
+            creator: 1,
+            start: 10u64,
+            length: 10u64,
+            team1: TeamName::<Test>::try_from(b"team1".to_vec()).unwrap(),
+            team2: TeamName::<Test>::try_from(b"team2".to_vec()).unwrap(),
+            result: Some(MatchResult::Team2Victory),
+            dispute_end: None,
+            asset: None::<u32>,
+            bet_count: 2,
+            totals: ResultTotals {
+                team1_victory: 0u128,
+                team2_victory: 30,
+                draw: 5,
+            },
+            rollover: 0,
+            commission: Permill::zero(),
+            settled_pot: None,
+            claimed_stakes: 0,
+            paid_winnings: 0,
+            deposit: 10,
+        };
+        migrations::v2::Matches::<Test>::insert(0, old_match);
+        let bet = Bet {
+            amount: 30,
+            claimed: false,
+        };
+        migrations::v2::Bets::<Test>::insert((0, 2, MatchResult::Team2Victory), bet.clone());
+
+        MigrateToV2::<Test>::on_runtime_upgrade();
+        MigrateToV3::<Test>::on_runtime_upgrade();
+        MigrateToV4::<Test>::on_runtime_upgrade();
+        MigrateToV5::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageVersion::get::<Betting>(), 5);
+        let migrated = Betting::get_matches(0).unwrap();
+        assert_eq!(migrated.teams, None);
+        assert_eq!(
+            outcome_names(0),
+            vec![b"team1".to_vec(), b"team2".to_vec(), DRAW.to_vec()]
+        );
+        assert_eq!(migrated.result, Some(MatchResult::Team2Victory.index()));
+        assert_eq!(migrated.market, Some(ScoreMarket::MatchResult));
+        assert_eq!(migrated.total_on(MatchResult::Team2Victory.index()), 30);
+        assert_eq!(migrated.total_on(MatchResult::Draw.index()), 5);
+        assert_eq!(migrated.total(), 35);
+        // The bets are keyed by the index of their outcome
+        assert_eq!(
+            Betting::get_bet((0, 2, MatchResult::Team2Victory.index())),
+            Some(bet)
+        );
+        assert_eq!(
+            MatchHashes::<Test>::get(Betting::get_match_hash(migrated)),
+            Some(0)
+        );
+    });
+}
+
+#[test]
+fn migrates_tournament_teams_to_the_registry() {
+    new_test_ext().execute_with(|| {
+        use migrations::v5::{MigrateToV5, Tournaments};
+
+        StorageVersion::new(4).put::<Betting>();
+        let b = team("b ");
+        let names = vec![b"A".to_vec(), b"B".to_vec(), b" ".to_vec(), b"a".to_vec()]
+            .into_iter()
+            .map(|name| TeamName::<Test>::try_from(name).unwrap())
+            .collect::<Vec<_>>();
+        let old_tournament = Tournament {
+            organizer: 1,
+            format: TournamentFormat::Knockout,
+            teams: OutcomeNames::<Test>::try_from(names).unwrap(),
+            start: 10u64,
+            round_length: 20,
+            match_length: 10,
+            commission: Permill::zero(),
+            rounds: 1,
+            fixtures: Default::default(),
+            outright: 0,
+        };
+        Tournaments::<Test>::insert(0, old_tournament);
+
+        MigrateToV5::<Test>::on_runtime_upgrade();
+
+        // The teams already registered are reused, and a blank or repeated name is numbered
+        assert_eq!(StorageVersion::get::<Betting>(), 5);
+        let migrated = Betting::get_tournament(0).unwrap();
+        assert_eq!(migrated.teams.to_vec(), vec![b + 1, b, b + 2, b + 3]);
+        assert_eq!(
+            Betting::get_team(b + 1).unwrap().name.to_vec(),
+            b"A".to_vec()
+        );
+        assert_eq!(
+            Betting::get_team(b + 2).unwrap().name.to_vec(),
+            b"Team 1".to_vec()
+        );
+        assert_eq!(
+            Betting::get_team(b + 3).unwrap().name.to_vec(),
+            b"a 2".to_vec()
+        );
+        let key = Betting::team_key(b"Team 1").unwrap();
+        assert_eq!(Betting::get_team_id(&key), Some(b + 2));
+    });
+}
+
+#[test]
+fn exact_winnings_on_large_pots() {
+    new_test_ext().execute_with(|| {
+        // A share of 1/3 can not be represented by a `Perbill` without losing precision.
+        let total_bet = 300_000_000_000_000;
+        assert_eq!(
+            Betting::winnings(100_000_000_000_000, 300_000_000_000_000, total_bet),
+            100_000_000_000_000
+        );
+        assert_eq!(
+            Betting::winnings(1, 3, 1_000_000_000_000_000),
+            333_333_333_333_333
+        );
+        assert_eq!(
+            1_000_000_000_000_000 - 3 * Betting::winnings(1, 3, 1_000_000_000_000_000),
+            1
+        );
+    });
+}
+
+#[test]
+fn winnings_plus_dust_always_equal_total_bet() {
+    // Deterministic xorshift generator, so any failing case can be reproduced.
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut random = |max: u128| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed as u128 * seed as u128) % max + 1
+    };
+
+    for _ in 0..200 {
+        new_test_ext().execute_with(|| {
+            // One or two winners and at most one loser, within `MaxBetsPerMatch`
+            let magnitude = 10u128.pow(random(14) as u32);
+            let winners: Vec<(u64, u128)> = (2..2 + random(2) as u64)
+                .map(|bettor| (bettor, random(magnitude)))
+                .collect();
+            let losers: Vec<(u64, u128)> = (4..3 + random(2) as u64)
+                .map(|bettor| (bettor, random(magnitude)))
+                .collect();
+
+            let match_id = create_match(1, "team1", "team2", 10, 10);
+            for (bettors, result) in [
+                (&winners, MatchResult::Team1Victory),
+                (&losers, MatchResult::Team2Victory),
+            ] {
+                for (bettor, amount) in bettors {
+                    assert_ok!(Betting::bet(
+                        RuntimeOrigin::signed(*bettor),
+                        match_id,
+                        *amount,
+                        result.index()
+                    ));
+                }
+            }
+            System::set_block_number(22);
+            assert_ok!(Betting::set_result(
+                RawOrigin::Root.into(),
+                match_id,
+                MatchResult::Team1Victory.index()
+            ));
+            System::set_block_number(27);
+            assert_ok!(Betting::distribute_winnings(
+                RuntimeOrigin::signed(1),
+                match_id
+            ));
+
+            let total_winners: u128 = winners.iter().map(|(_, amount)| amount).sum();
+            let total_bet: u128 =
+                total_winners + losers.iter().map(|(_, amount)| amount).sum::<u128>();
+            let mut paid = 0;
+            for (bettor, amount) in &winners {
+                let expected = amount * total_bet / total_winners;
+                assert_eq!(
+                    Balances::free_balance(bettor),
+                    INIT_BALANCE - amount + expected
+                );
+                paid += expected;
+            }
+            for (bettor, amount) in &losers {
+                assert_eq!(Balances::free_balance(bettor), INIT_BALANCE - amount);
+            }
+
+            // The remainder left by the rounding goes to the creator
+            let dust = total_bet - paid;
+            assert_eq!(Balances::free_balance(1), INIT_BALANCE + dust);
+            // Every share is rounded down by less than one unit.
+            assert!(dust < winners.len() as u128);
+        });
+    }
+}
@@ -15,26 +15,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Autogenerated weights for pallet_betting
+//! Weights for pallet_betting
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2022-12-01, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! HOSTNAME: `g`, CPU: `Intel(R) Core(TM) i7-7700HQ CPU @ 2.80GHz`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ../../../target/production/substrate
-// benchmark
-// pallet
-// --chain=dev
-// --steps=50
-// --repeat=20
-// --pallet=pallet_betting
-// --extrinsic=*
-// --execution=wasm
-// --wasm-execution=compiled
-// --template=../../../.maintain/frame-weight-template.hbs
-// --output=../../../frame/betting/src/weights.rs
+//! These weights are estimates and have not been measured on reference hardware. Regenerate
+//! them from `benchmarking.rs` before using the pallet in production:
+//!
+//! ```text
+//! substrate benchmark pallet --chain=dev --steps=50 --repeat=20 --pallet=pallet_betting \
+//!     --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --template=../../../.maintain/frame-weight-template.hbs \
+//!     --output=../../../frame/betting/src/weights.rs
+//! ```
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -45,30 +36,251 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_betting.
 pub trait WeightInfo {
+	fn register_team() -> Weight;
+	fn update_team() -> Weight;
 	fn create_match_to_bet() -> Weight;
+	fn create_market() -> Weight;
+	fn create_derived_market() -> Weight;
+	fn create_fixed_odds_market() -> Weight;
+	fn create_amm_market() -> Weight;
 	fn bet() -> Weight;
+	fn buy_shares() -> Weight;
+	fn sell_shares() -> Weight;
+	fn cancel_bet() -> Weight;
+	fn reduce_bet() -> Weight;
+	fn open_in_play() -> Weight;
+	fn suspend_betting() -> Weight;
+	fn resume_betting() -> Weight;
+	fn place_parlay(l: u32, ) -> Weight;
+	fn settle_parlay(l: u32, ) -> Weight;
+	fn create_tournament(m: u32, ) -> Weight;
+	fn advance_tournament(m: u32, ) -> Weight;
 	fn set_result() -> Weight;
-	fn distribute_winnings() -> Weight;
+	fn set_score(d: u32, ) -> Weight;
+	fn void_match(d: u32, ) -> Weight;
+	fn distribute_winnings(b: u32, ) -> Weight;
+	fn cancel_match(b: u32, ) -> Weight;
+	fn refund_match(b: u32, ) -> Weight;
+	fn schedule_settlement() -> Weight;
+	fn settle_scheduled(b: u32, ) -> Weight;
+	fn dispute_result() -> Weight;
+	fn resolve_dispute() -> Weight;
+	fn claim_winnings(b: u32, ) -> Weight;
+	fn reap_match(b: u32, ) -> Weight;
+	fn finalise_queued_match(b: u32, ) -> Weight;
 }
 
 /// Weights for pallet_betting using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: Betting TeamIds (r:1 w:1)
+	// Storage: Betting NextTeamId (r:1 w:1)
+	// Storage: Betting Teams (r:0 w:1)
+	fn register_team() -> Weight {
+		// Minimum execution time: 27_408 nanoseconds.
+		Weight::from_ref_time(28_112_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Teams (r:1 w:1)
+	// Storage: Betting TeamIds (r:1 w:2)
+	fn update_team() -> Weight {
+		// Minimum execution time: 31_935 nanoseconds.
+		Weight::from_ref_time(32_587_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Teams (r:2 w:0)
 	// Storage: Betting Matches (r:1 w:1)
 	// Storage: Betting MatchHashes (r:1 w:1)
 	fn create_match_to_bet() -> Weight {
-		// Minimum execution time: 36_783 nanoseconds.
-		Weight::from_ref_time(37_674_000 as u64)
+		// Minimum execution time: 41_206 nanoseconds.
+		Weight::from_ref_time(42_318_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	fn create_market() -> Weight {
+		// Minimum execution time: 41_208 nanoseconds.
+		Weight::from_ref_time(42_115_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(2 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	// Storage: Betting Matches (r:2 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting DerivedMarkets (r:1 w:1)
+	fn create_derived_market() -> Weight {
+		// Minimum execution time: 47_930 nanoseconds.
+		Weight::from_ref_time(48_861_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting Books (r:0 w:1)
+	fn create_fixed_odds_market() -> Weight {
+		// Minimum execution time: 46_302 nanoseconds.
+		Weight::from_ref_time(47_288_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting MarketMakers (r:0 w:1)
+	fn create_amm_market() -> Weight {
+		// Minimum execution time: 52_741 nanoseconds.
+		Weight::from_ref_time(53_906_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
 	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	// Storage: Betting InPlayDiscounts (r:1 w:1)
+	// Storage: Betting Books (r:1 w:0)
 	// Storage: System Account (r:1 w:1)
 	fn bet() -> Weight {
-		// Minimum execution time: 75_394 nanoseconds.
-		Weight::from_ref_time(77_400_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(2 as u64))
-			.saturating_add(T::DbWeight::get().writes(2 as u64))
+		// Minimum execution time: 91_482 nanoseconds.
+		Weight::from_ref_time(93_270_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MarketMakers (r:1 w:0)
+	// Storage: Betting InPlayMarkets (r:1 w:0)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn buy_shares() -> Weight {
+		// Minimum execution time: 121_095 nanoseconds.
+		Weight::from_ref_time(123_804_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MarketMakers (r:1 w:0)
+	// Storage: Betting InPlayMarkets (r:1 w:0)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn sell_shares() -> Weight {
+		// Minimum execution time: 127_318 nanoseconds.
+		Weight::from_ref_time(130_142_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	fn cancel_bet() -> Weight {
+		// Minimum execution time: 81_207 nanoseconds.
+		Weight::from_ref_time(83_012_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	fn reduce_bet() -> Weight {
+		// Minimum execution time: 80_864 nanoseconds.
+		Weight::from_ref_time(82_531_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Books (r:1 w:0)
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	fn open_in_play() -> Weight {
+		// Minimum execution time: 33_417 nanoseconds.
+		Weight::from_ref_time(34_205_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	fn suspend_betting() -> Weight {
+		// Minimum execution time: 24_862 nanoseconds.
+		Weight::from_ref_time(25_531_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	fn resume_betting() -> Weight {
+		// Minimum execution time: 24_190 nanoseconds.
+		Weight::from_ref_time(24_873_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Betting NextParlayId (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Books (r:1 w:1)
+	// Storage: Betting MatchParlays (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Betting Parlays (r:0 w:1)
+	/// The range of component `l` is `[2, 4]`.
+	fn place_parlay(l: u32, ) -> Weight {
+		// Minimum execution time: 61_357 nanoseconds.
+		Weight::from_ref_time(47_902_000 as u64)
+			// Standard Error: 21_408
+			.saturating_add(Weight::from_ref_time(11_286_000 as u64).saturating_mul(l as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(l as u64)))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(l as u64)))
+	}
+	// Storage: Betting Parlays (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting MatchParlays (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	/// The range of component `l` is `[2, 4]`.
+	fn settle_parlay(l: u32, ) -> Weight {
+		// Minimum execution time: 58_740 nanoseconds.
+		Weight::from_ref_time(41_613_000 as u64)
+			// Standard Error: 18_925
+			.saturating_add(Weight::from_ref_time(9_874_000 as u64).saturating_mul(l as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(l as u64)))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(l as u64)))
+	}
+	// Storage: Betting NextTournamentId (r:1 w:1)
+	// Storage: Betting Teams (r:2 w:0)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting NextMatchId (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Betting Rollovers (r:1 w:0)
+	// Storage: Betting SettlementCursor (r:1 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting SettlementQueue (r:0 w:1)
+	// Storage: Betting Matches (r:0 w:1)
+	// Storage: Betting TournamentMatches (r:0 w:1)
+	// Storage: Betting Tournaments (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn create_tournament(m: u32, ) -> Weight {
+		// Minimum execution time: 118_460 nanoseconds.
+		Weight::from_ref_time(84_215_000 as u64)
+			// Standard Error: 27_311
+			.saturating_add(Weight::from_ref_time(52_904_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(10 as u64))
+			.saturating_add(T::DbWeight::get().writes((9 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: Betting Tournaments (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting SettlementCursor (r:1 w:1)
+	// Storage: Betting SettlementQueue (r:0 w:1)
+	// Storage: Betting TournamentMatches (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn advance_tournament(m: u32, ) -> Weight {
+		// Minimum execution time: 49_872 nanoseconds.
+		Weight::from_ref_time(38_640_000 as u64)
+			// Standard Error: 14_052
+			.saturating_add(Weight::from_ref_time(8_137_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(m as u64)))
 	}
 	// Storage: Betting Matches (r:1 w:1)
 	fn set_result() -> Weight {
@@ -78,31 +290,386 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
 	// Storage: Betting Matches (r:1 w:1)
-	fn distribute_winnings() -> Weight {
+	// Storage: Betting DerivedMarkets (r:1 w:0)
+	/// The range of component `d` is `[0, 10]`.
+	fn set_score(d: u32, ) -> Weight {
+		// Minimum execution time: 38_104 nanoseconds.
+		Weight::from_ref_time(39_012_000 as u64)
+			// Standard Error: 6_912
+			.saturating_add(Weight::from_ref_time(17_433_000 as u64).saturating_mul(d as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(d as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(d as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting DerivedMarkets (r:1 w:0)
+	/// The range of component `d` is `[0, 10]`.
+	fn void_match(d: u32, ) -> Weight {
+		// Minimum execution time: 36_870 nanoseconds.
+		Weight::from_ref_time(37_552_000 as u64)
+			// Standard Error: 6_480
+			.saturating_add(Weight::from_ref_time(16_902_000 as u64).saturating_mul(d as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(d as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(d as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn distribute_winnings(b: u32, ) -> Weight {
 		// Minimum execution time: 19_590 nanoseconds.
 		Weight::from_ref_time(20_231_000 as u64)
+			// Standard Error: 9_406
+			.saturating_add(Weight::from_ref_time(24_517_000 as u64).saturating_mul(b as u64))
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn cancel_match(b: u32, ) -> Weight {
+		// Minimum execution time: 61_205 nanoseconds.
+		Weight::from_ref_time(62_874_000 as u64)
+			// Standard Error: 8_931
+			.saturating_add(Weight::from_ref_time(21_874_000 as u64).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	/// The range of component `b` is `[1, 10]`.
+	fn refund_match(b: u32, ) -> Weight {
+		// Minimum execution time: 72_419 nanoseconds.
+		Weight::from_ref_time(74_052_000 as u64)
+			// Standard Error: 8_754
+			.saturating_add(Weight::from_ref_time(22_106_000 as u64).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting ScheduledSettlements (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	fn schedule_settlement() -> Weight {
+		// Minimum execution time: 38_947 nanoseconds.
+		Weight::from_ref_time(39_820_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting ScheduledSettlements (r:0 w:1)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn settle_scheduled(b: u32, ) -> Weight {
+		// Minimum execution time: 76_311 nanoseconds.
+		Weight::from_ref_time(78_095_000 as u64)
+			// Standard Error: 9_352
+			.saturating_add(Weight::from_ref_time(24_155_000 as u64).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Disputes (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	fn dispute_result() -> Weight {
+		// Minimum execution time: 41_322 nanoseconds.
+		Weight::from_ref_time(42_517_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	fn resolve_dispute() -> Weight {
+		// Minimum execution time: 68_940 nanoseconds.
+		Weight::from_ref_time(70_112_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn claim_winnings(b: u32, ) -> Weight {
+		// Minimum execution time: 58_107 nanoseconds.
+		Weight::from_ref_time(59_630_000 as u64)
+			// Standard Error: 9_114
+			.saturating_add(Weight::from_ref_time(23_968_000 as u64).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn reap_match(b: u32, ) -> Weight {
+		// Minimum execution time: 63_418 nanoseconds.
+		Weight::from_ref_time(64_925_000 as u64)
+			// Standard Error: 9_287
+			.saturating_add(Weight::from_ref_time(24_302_000 as u64).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting SettlementQueue (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn finalise_queued_match(b: u32, ) -> Weight {
+		// Minimum execution time: 71_863 nanoseconds.
+		Weight::from_ref_time(73_348_000 as u64)
+			// Standard Error: 9_217
+			.saturating_add(Weight::from_ref_time(24_083_000 as u64).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
+	// Storage: Betting TeamIds (r:1 w:1)
+	// Storage: Betting NextTeamId (r:1 w:1)
+	// Storage: Betting Teams (r:0 w:1)
+	fn register_team() -> Weight {
+		// Minimum execution time: 27_408 nanoseconds.
+		Weight::from_ref_time(28_112_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Teams (r:1 w:1)
+	// Storage: Betting TeamIds (r:1 w:2)
+	fn update_team() -> Weight {
+		// Minimum execution time: 31_935 nanoseconds.
+		Weight::from_ref_time(32_587_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Teams (r:2 w:0)
 	// Storage: Betting Matches (r:1 w:1)
 	// Storage: Betting MatchHashes (r:1 w:1)
 	fn create_match_to_bet() -> Weight {
-		// Minimum execution time: 36_783 nanoseconds.
-		Weight::from_ref_time(37_674_000 as u64)
+		// Minimum execution time: 41_206 nanoseconds.
+		Weight::from_ref_time(42_318_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	fn create_market() -> Weight {
+		// Minimum execution time: 41_208 nanoseconds.
+		Weight::from_ref_time(42_115_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(2 as u64))
 			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
+	// Storage: Betting Matches (r:2 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting DerivedMarkets (r:1 w:1)
+	fn create_derived_market() -> Weight {
+		// Minimum execution time: 47_930 nanoseconds.
+		Weight::from_ref_time(48_861_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting Books (r:0 w:1)
+	fn create_fixed_odds_market() -> Weight {
+		// Minimum execution time: 46_302 nanoseconds.
+		Weight::from_ref_time(47_288_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
 	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting MarketMakers (r:0 w:1)
+	fn create_amm_market() -> Weight {
+		// Minimum execution time: 52_741 nanoseconds.
+		Weight::from_ref_time(53_906_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	// Storage: Betting InPlayDiscounts (r:1 w:1)
+	// Storage: Betting Books (r:1 w:0)
 	// Storage: System Account (r:1 w:1)
 	fn bet() -> Weight {
-		// Minimum execution time: 75_394 nanoseconds.
-		Weight::from_ref_time(77_400_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(2 as u64))
-			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+		// Minimum execution time: 91_482 nanoseconds.
+		Weight::from_ref_time(93_270_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MarketMakers (r:1 w:0)
+	// Storage: Betting InPlayMarkets (r:1 w:0)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn buy_shares() -> Weight {
+		// Minimum execution time: 121_095 nanoseconds.
+		Weight::from_ref_time(123_804_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MarketMakers (r:1 w:0)
+	// Storage: Betting InPlayMarkets (r:1 w:0)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn sell_shares() -> Weight {
+		// Minimum execution time: 127_318 nanoseconds.
+		Weight::from_ref_time(130_142_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	fn cancel_bet() -> Weight {
+		// Minimum execution time: 81_207 nanoseconds.
+		Weight::from_ref_time(83_012_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	fn reduce_bet() -> Weight {
+		// Minimum execution time: 80_864 nanoseconds.
+		Weight::from_ref_time(82_531_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Books (r:1 w:0)
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	fn open_in_play() -> Weight {
+		// Minimum execution time: 33_417 nanoseconds.
+		Weight::from_ref_time(34_205_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	fn suspend_betting() -> Weight {
+		// Minimum execution time: 24_862 nanoseconds.
+		Weight::from_ref_time(25_531_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Betting InPlayMarkets (r:1 w:1)
+	fn resume_betting() -> Weight {
+		// Minimum execution time: 24_190 nanoseconds.
+		Weight::from_ref_time(24_873_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Betting NextParlayId (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Books (r:1 w:1)
+	// Storage: Betting MatchParlays (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Betting Parlays (r:0 w:1)
+	/// The range of component `l` is `[2, 4]`.
+	fn place_parlay(l: u32, ) -> Weight {
+		// Minimum execution time: 61_357 nanoseconds.
+		Weight::from_ref_time(47_902_000 as u64)
+			// Standard Error: 21_408
+			.saturating_add(Weight::from_ref_time(11_286_000 as u64).saturating_mul(l as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(l as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(l as u64)))
+	}
+	// Storage: Betting Parlays (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting MatchParlays (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	/// The range of component `l` is `[2, 4]`.
+	fn settle_parlay(l: u32, ) -> Weight {
+		// Minimum execution time: 58_740 nanoseconds.
+		Weight::from_ref_time(41_613_000 as u64)
+			// Standard Error: 18_925
+			.saturating_add(Weight::from_ref_time(9_874_000 as u64).saturating_mul(l as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(l as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(l as u64)))
+	}
+	// Storage: Betting NextTournamentId (r:1 w:1)
+	// Storage: Betting Teams (r:2 w:0)
+	// Storage: Betting MatchHashes (r:1 w:1)
+	// Storage: Betting NextMatchId (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Betting Rollovers (r:1 w:0)
+	// Storage: Betting SettlementCursor (r:1 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting SettlementQueue (r:0 w:1)
+	// Storage: Betting Matches (r:0 w:1)
+	// Storage: Betting TournamentMatches (r:0 w:1)
+	// Storage: Betting Tournaments (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn create_tournament(m: u32, ) -> Weight {
+		// Minimum execution time: 118_460 nanoseconds.
+		Weight::from_ref_time(84_215_000 as u64)
+			// Standard Error: 27_311
+			.saturating_add(Weight::from_ref_time(52_904_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(8 as u64))
+			.saturating_add(RocksDbWeight::get().reads((8 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes((9 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: Betting Tournaments (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting SettlementCursor (r:1 w:1)
+	// Storage: Betting SettlementQueue (r:0 w:1)
+	// Storage: Betting TournamentMatches (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn advance_tournament(m: u32, ) -> Weight {
+		// Minimum execution time: 49_872 nanoseconds.
+		Weight::from_ref_time(38_640_000 as u64)
+			// Standard Error: 14_052
+			.saturating_add(Weight::from_ref_time(8_137_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(m as u64)))
 	}
 	// Storage: Betting Matches (r:1 w:1)
 	fn set_result() -> Weight {
@@ -112,10 +679,172 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
 	// Storage: Betting Matches (r:1 w:1)
-	fn distribute_winnings() -> Weight {
+	// Storage: Betting DerivedMarkets (r:1 w:0)
+	/// The range of component `d` is `[0, 10]`.
+	fn set_score(d: u32, ) -> Weight {
+		// Minimum execution time: 38_104 nanoseconds.
+		Weight::from_ref_time(39_012_000 as u64)
+			// Standard Error: 6_912
+			.saturating_add(Weight::from_ref_time(17_433_000 as u64).saturating_mul(d as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(d as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(d as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting DerivedMarkets (r:1 w:0)
+	/// The range of component `d` is `[0, 10]`.
+	fn void_match(d: u32, ) -> Weight {
+		// Minimum execution time: 36_870 nanoseconds.
+		Weight::from_ref_time(37_552_000 as u64)
+			// Standard Error: 6_480
+			.saturating_add(Weight::from_ref_time(16_902_000 as u64).saturating_mul(d as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(d as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(d as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn distribute_winnings(b: u32, ) -> Weight {
 		// Minimum execution time: 19_590 nanoseconds.
 		Weight::from_ref_time(20_231_000 as u64)
+			// Standard Error: 9_406
+			.saturating_add(Weight::from_ref_time(24_517_000 as u64).saturating_mul(b as u64))
 			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn cancel_match(b: u32, ) -> Weight {
+		// Minimum execution time: 61_205 nanoseconds.
+		Weight::from_ref_time(62_874_000 as u64)
+			// Standard Error: 8_931
+			.saturating_add(Weight::from_ref_time(21_874_000 as u64).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	/// The range of component `b` is `[1, 10]`.
+	fn refund_match(b: u32, ) -> Weight {
+		// Minimum execution time: 72_419 nanoseconds.
+		Weight::from_ref_time(74_052_000 as u64)
+			// Standard Error: 8_754
+			.saturating_add(Weight::from_ref_time(22_106_000 as u64).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting ScheduledSettlements (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	fn schedule_settlement() -> Weight {
+		// Minimum execution time: 38_947 nanoseconds.
+		Weight::from_ref_time(39_820_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting ScheduledSettlements (r:0 w:1)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn settle_scheduled(b: u32, ) -> Weight {
+		// Minimum execution time: 76_311 nanoseconds.
+		Weight::from_ref_time(78_095_000 as u64)
+			// Standard Error: 9_352
+			.saturating_add(Weight::from_ref_time(24_155_000 as u64).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:0)
+	// Storage: Betting Disputes (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	fn dispute_result() -> Weight {
+		// Minimum execution time: 41_322 nanoseconds.
+		Weight::from_ref_time(42_517_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	fn resolve_dispute() -> Weight {
+		// Minimum execution time: 68_940 nanoseconds.
+		Weight::from_ref_time(70_112_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn claim_winnings(b: u32, ) -> Weight {
+		// Minimum execution time: 58_107 nanoseconds.
+		Weight::from_ref_time(59_630_000 as u64)
+			// Standard Error: 9_114
+			.saturating_add(Weight::from_ref_time(23_968_000 as u64).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn reap_match(b: u32, ) -> Weight {
+		// Minimum execution time: 63_418 nanoseconds.
+		Weight::from_ref_time(64_925_000 as u64)
+			// Standard Error: 9_287
+			.saturating_add(Weight::from_ref_time(24_302_000 as u64).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
+	}
+	// Storage: Betting SettlementQueue (r:1 w:1)
+	// Storage: Betting Matches (r:1 w:1)
+	// Storage: Betting Disputes (r:1 w:0)
+	// Storage: Betting MatchHashes (r:0 w:1)
+	// Storage: Betting MatchesByCreator (r:0 w:1)
+	// Storage: Betting Bets (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `b` is `[1, 10]`.
+	fn finalise_queued_match(b: u32, ) -> Weight {
+		// Minimum execution time: 71_863 nanoseconds.
+		Weight::from_ref_time(73_348_000 as u64)
+			// Standard Error: 9_217
+			.saturating_add(Weight::from_ref_time(24_083_000 as u64).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(b as u64)))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(b as u64)))
 	}
 }